@@ -0,0 +1,368 @@
+//ABT with dynamic ordering (Zivan & Meisels), simulated in synchronous rounds
+//with a message queue per node. Unlike abt_sequential the priority of a node
+//is not its index: a node that gets a nogood and has to change its color
+//reorders the nodes below it, fewest free colors first, and tells them with an
+//order message. Every order carries a counter per position and a node only
+//adopts an order whose counters are lexicographically larger than its own, so
+//concurrent proposals settle on one order. Nogoods carry the colors they
+//depend on, so a proof that the graph is uncolorable is sound.
+//
+//Value precedence needs a fixed order, so symmetry breaking here is the
+//weaker unary form: node i only takes one of the first i + 1 colors, which
+//every coloring satisfies once its colors are numbered by first use.
+use std::collections::HashMap;
+use std::rc::Rc;
+use crate::{check_coloring, reset_graph, Color, Node, Outcome};
+
+pub struct AbtDoResult{
+    pub outcome: Outcome,
+    pub rounds: usize,
+    pub reorders: usize,
+}
+
+struct Order{
+    rank: Vec<usize>,//rank[node] is its position, 0 is the highest priority
+    stamps: Vec<usize>,
+}
+
+enum Message{
+    Ok(usize, Color),
+    //the colors that cannot all hold together, the receiver's included
+    Nogood(usize, Vec<(usize, Color)>),
+    Order(Rc<Order>),
+    //the sender wants to hear about our color changes
+    Link(usize),
+}
+
+struct Agent{
+    color: Option<Color>,
+    view: HashMap<usize, Color>,
+    links: Vec<usize>,//nodes told about our color changes
+    asked: Vec<usize>,//nodes we asked to tell us
+    //one nogood per color: the other colors that rule it out
+    nogoods: Vec<Option<Vec<(usize, Color)>>>,
+    order: Rc<Order>,
+    got_nogood: bool,
+    domain: usize,//the node may use the first domain colors
+}
+
+impl Agent{
+    fn higher(&self, a: usize, b: usize) -> bool{
+        self.order.rank[a] < self.order.rank[b]
+    }
+
+    //a stored nogood only counts while its nodes are above us and still have
+    //the colors it names
+    fn justified(&self, me: usize, color: Color) -> Option<&Vec<(usize, Color)>>{
+        let nogood = self.nogoods[color.index()].as_ref()?;
+        let holds = nogood.iter().all(|(node, node_color)| {
+            self.higher(*node, me) && self.view.get(node) == Some(node_color)
+        });
+        if holds {Some(nogood)} else {None}
+    }
+
+    //the highest priority neighbor above us that has color
+    fn culprit(&self, me: usize, node: &Node, color: Color) -> Option<usize>{
+        node.neighbors.iter()
+            .filter(|neighbor| self.higher(**neighbor, me) && self.view.get(neighbor) == Some(&color))
+            .min_by_key(|neighbor| self.order.rank[**neighbor])
+            .copied()
+    }
+
+    fn allowed(&self, me: usize, node: &Node, color: Color) -> bool{
+        self.culprit(me, node, color).is_none() && self.justified(me, color).is_none()
+    }
+}
+
+struct Network{
+    inbox: Vec<Vec<Message>>,
+    outbox: Vec<Vec<Message>>,
+    sent: usize,
+}
+
+impl Network{
+    fn send(&mut self, to: usize, message: Message){
+        self.outbox[to].push(message);
+        self.sent += 1;
+    }
+}
+
+fn send_oks(agent: &Agent, me: usize, network: &mut Network){
+    if let Some(color) = agent.color{
+        for link in &agent.links{
+            network.send(*link, Message::Ok(me, color));
+        }
+    }
+}
+
+fn receive(agents: &mut [Agent], graph: &[Node], me: usize, message: Message, network: &mut Network){
+    let agent = &mut agents[me];
+    match message{
+        Message::Ok(sender, color) => {
+            agent.view.insert(sender, color);
+            //drop the nogoods that rely on the sender's old color
+            for nogood in agent.nogoods.iter_mut(){
+                if nogood.as_ref().is_some_and(|nogood| {
+                    nogood.iter().any(|(node, node_color)| *node == sender && *node_color != color)
+                }){
+                    *nogood = None;
+                }
+            }
+        },
+        Message::Link(sender) => {
+            if !agent.links.contains(&sender){
+                agent.links.push(sender);
+            }
+            if let Some(color) = agent.color{
+                network.send(sender, Message::Ok(me, color));
+            }
+        },
+        Message::Order(order) => {
+            if order.stamps > agent.order.stamps{
+                agent.order = order;
+            }
+        },
+        Message::Nogood(sender, nogood) => {
+            //the sender forgot our color when it sent the nogood
+            if !agent.links.contains(&sender){
+                agent.links.push(sender);
+            }
+            let own = nogood.iter().find(|(node, _)| *node == me).map(|(_, color)| *color);
+            let obsolete = own != agent.color || nogood.iter().any(|(node, color)| {
+                *node != me && agent.view.get(node).is_some_and(|known| known != color)
+            });
+            if obsolete{
+                if let Some(color) = agent.color{
+                    network.send(sender, Message::Ok(me, color));
+                }
+                return;
+            }
+            let mut others = Vec::new();
+            for (node, color) in nogood{
+                if node == me{
+                    continue;
+                }
+                agent.view.entry(node).or_insert(color);
+                if !graph[me].neighbors.contains(&node) && !agent.asked.contains(&node){
+                    agent.asked.push(node);
+                    network.send(node, Message::Link(me));
+                }
+                others.push((node, color));
+            }
+            agent.nogoods[own.unwrap().index()] = Some(others);
+            agent.got_nogood = true;
+        },
+    }
+}
+
+//sort the nodes below me by how many colors the nodes down to me leave them
+fn reorder(agents: &mut [Agent], graph: &[Node], me: usize, num_colors: usize, network: &mut Network){
+    let agent = &agents[me];
+    let mut order: Vec<usize> = (0..graph.len()).collect();
+    order.sort_by_key(|node| agent.order.rank[*node]);
+    let position = agent.order.rank[me];
+    let colors = |node: usize| -> Option<Color>{
+        if node == me {agent.color} else {agent.view.get(&node).copied()}
+    };
+    let mut used = vec![false; num_colors];
+    let mut free_colors = |node: usize| -> usize{
+        used.iter_mut().for_each(|used| *used = false);
+        for neighbor in &graph[node].neighbors{
+            if agent.order.rank[*neighbor] <= position{
+                if let Some(color) = colors(*neighbor){
+                    used[color.index()] = true;
+                }
+            }
+        }
+        used.iter().filter(|used| !**used).count()
+    };
+    order[(position + 1)..].sort_by_cached_key(|node| (free_colors(*node), *node));
+
+    let mut rank = vec![0; graph.len()];
+    for (position, node) in order.iter().enumerate(){
+        rank[*node] = position;
+    }
+    let mut stamps = agent.order.stamps.clone();
+    stamps[position] += 1;
+    for stamp in &mut stamps[(position + 1)..]{
+        *stamp = 0;
+    }
+    let new_order = Rc::new(Order{rank, stamps});
+    agents[me].order = new_order.clone();
+    for node in &order[(position + 1)..]{
+        network.send(*node, Message::Order(new_order.clone()));
+    }
+}
+
+//choose a color consistent with the nodes above, or send nogoods until one
+//is left; returns false if the node proved that there is no coloring
+fn step(agents: &mut [Agent], graph: &[Node], me: usize, num_colors: usize,
+        network: &mut Network, reorders: &mut usize) -> bool{
+    let colors = Color::vector_of_colors(agents[me].domain);
+    let agent = &mut agents[me];
+    if agent.color.is_some_and(|color| agent.allowed(me, &graph[me], color)){
+        //a nogood that did not rule out our color came from a node with
+        //another order
+        if agent.got_nogood{
+            agent.got_nogood = false;
+            send_oks(agent, me, network);
+        }
+        return true;
+    }
+    loop{
+        let agent = &mut agents[me];
+        if let Some(color) = colors.iter().find(|color| agent.allowed(me, &graph[me], **color)){
+            agent.color = Some(*color);
+            send_oks(agent, me, network);
+            if agent.got_nogood{
+                agent.got_nogood = false;
+                reorder(agents, graph, me, num_colors, network);
+                *reorders += 1;
+            }
+            return true;
+        }
+        //every color is ruled out: resolve the reasons into one nogood
+        let mut nogood: Vec<(usize, Color)> = Vec::new();
+        for color in &colors{
+            let reasons = match agent.culprit(me, &graph[me], *color){
+                Some(node) => vec![(node, *color)],
+                None => agent.justified(me, *color).unwrap().clone(),
+            };
+            for reason in reasons{
+                if !nogood.contains(&reason){
+                    nogood.push(reason);
+                }
+            }
+        }
+        let target = match nogood.iter().max_by_key(|(node, _)| agent.order.rank[*node]){
+            Some((node, _)) => *node,
+            None => return false,
+        };
+        network.send(target, Message::Nogood(me, nogood));
+        agent.view.remove(&target);
+    }
+}
+
+//colors the graph with ABT_DO; max_rounds bounds the rounds as in
+//abt_sequential
+pub fn abt_do(graph: &mut [Node], num_colors: usize, max_rounds: Option<usize>,
+              break_symmetry: bool) -> AbtDoResult{
+    reset_graph(graph, num_colors);
+    let num_nodes = graph.len();
+    let order = Rc::new(Order{rank: (0..num_nodes).collect(), stamps: vec![0; num_nodes]});
+    let mut agents: Vec<Agent> = graph.iter().enumerate().map(|(index, node)| Agent{
+        color: None,
+        view: HashMap::new(),
+        links: node.neighbors.clone(),
+        asked: Vec::new(),
+        nogoods: vec![None; num_colors],
+        order: order.clone(),
+        got_nogood: false,
+        domain: if break_symmetry {num_colors.min(index + 1)} else {num_colors},
+    }).collect();
+    let mut network = Network{
+        inbox: (0..num_nodes).map(|_| Vec::new()).collect(),
+        outbox: (0..num_nodes).map(|_| Vec::new()).collect(),
+        sent: 0,
+    };
+
+    let mut rounds = 0;
+    let mut reorders = 0;
+    let mut outcome = Outcome::Colored;
+    loop{
+        if max_rounds.is_some_and(|max| rounds >= max){
+            outcome = Outcome::GaveUp;
+            break;
+        }
+        rounds += 1;
+        network.sent = 0;
+        for me in 0..num_nodes{
+            let messages = std::mem::take(&mut network.inbox[me]);
+            if rounds > 1 && messages.is_empty() && !agents[me].got_nogood{
+                continue;
+            }
+            for message in messages{
+                receive(&mut agents, graph, me, message, &mut network);
+            }
+            if !step(&mut agents, graph, me, num_colors, &mut network, &mut reorders){
+                outcome = Outcome::NoSolution;
+            }
+        }
+        if outcome == Outcome::NoSolution{
+            break;
+        }
+        std::mem::swap(&mut network.inbox, &mut network.outbox);
+        if network.sent == 0{
+            break;
+        }
+    }
+
+    for (node, agent) in graph.iter_mut().zip(&agents){
+        node.color = agent.color;
+    }
+    if outcome == Outcome::Colored && !check_coloring(graph).is_valid(){
+        outcome = Outcome::GaveUp;
+    }
+    AbtDoResult{outcome, rounds, reorders}
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use crate::generators::{self, Rng};
+    use crate::{all_consistent, exhaustive_search};
+
+    #[test]
+    fn abt_do_colors_graphs(){
+        let cases = vec![
+            (generators::mycielski(3), 4),
+            (generators::mycielski(4), 5),
+            (generators::queen_graph(5, 5), 5),
+            (generators::planted(60, 4, 0.3, &mut Rng::new(3)).0, 4),
+        ];
+        for (mut graph, num_colors) in cases{
+            for break_symmetry in [false, true]{
+                let result = abt_do(&mut graph, num_colors, None, break_symmetry);
+                assert_eq!(result.outcome, Outcome::Colored);
+                assert!(all_consistent(&graph));
+            }
+        }
+    }
+
+    #[test]
+    fn abt_do_proves_uncolorable(){
+        let cases = vec![
+            (generators::mycielski(3), 3),
+            (generators::queen_graph(1, 5), 4),
+            (generators::queen_graph(5, 5), 4),
+        ];
+        for (mut graph, num_colors) in cases{
+            assert_eq!(abt_do(&mut graph, num_colors, None, false).outcome, Outcome::NoSolution);
+            assert_eq!(abt_do(&mut graph, num_colors, None, true).outcome, Outcome::NoSolution);
+        }
+    }
+
+    #[test]
+    fn abt_do_agrees_with_exhaustive(){
+        let mut rng = Rng::new(8);
+        for _ in 0..20{
+            let mut graph = generators::gnp(12, 0.4, &mut rng);
+            for num_colors in 2..5{
+                let expected = exhaustive_search(&mut graph, num_colors);
+                for break_symmetry in [false, true]{
+                    let result = abt_do(&mut graph, num_colors, None, break_symmetry);
+                    assert_eq!(result.outcome == Outcome::Colored, expected);
+                    assert_ne!(result.outcome, Outcome::GaveUp);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn abt_do_round_limit(){
+        let mut graph = generators::mycielski(5);
+        let result = abt_do(&mut graph, 5, Some(3), false);
+        assert_eq!(result.outcome, Outcome::GaveUp);
+        assert_eq!(result.rounds, 3);
+    }
+}
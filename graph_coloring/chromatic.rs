@@ -0,0 +1,178 @@
+//chromatic number search: a greedy coloring gives the first upper bound, a
+//greedy clique the first lower bound, and a decision solver is then asked
+//for one color less than the best coloring so far until it proves that
+//count impossible or gives up
+use std::time::{Duration, Instant};
+use crate::greedy;
+use crate::{check_coloring, Color, Node, Outcome};
+
+//one call of the decision solver
+pub struct Attempt{
+    pub num_colors: usize,
+    pub outcome: Outcome,
+    pub duration: Duration,
+}
+
+pub struct ChromaticResult{
+    pub lower: usize,//the chromatic number lies in lower..=upper
+    pub upper: usize,
+    pub greedy_colors: usize,//the DSATUR bound the search started from
+    pub greedy_duration: Duration,
+    pub attempts: Vec<Attempt>,
+}
+
+impl ChromaticResult{
+    pub fn is_exact(&self) -> bool{
+        self.lower == self.upper
+    }
+}
+
+//size of a clique found greedily from every start node; a lower bound
+pub fn greedy_clique(graph: &[Node]) -> usize{
+    let mut best = if graph.is_empty() {0} else {1};
+    let mut mark = vec![0; graph.len()];
+    let mut step = 0;
+    for start in 0..graph.len(){
+        if graph[start].neighbors.len() < best{
+            continue;//cannot beat best
+        }
+        let mut size = 1;
+        let mut candidates = graph[start].neighbors.clone();
+        while !candidates.is_empty(){
+            //grow the clique by the candidate with the largest degree
+            let pick = *candidates.iter().max_by_key(|index| graph[**index].neighbors.len()).unwrap();
+            size += 1;
+            step += 1;
+            for neighbor in &graph[pick].neighbors{
+                mark[*neighbor] = step;
+            }
+            candidates.retain(|index| mark[*index] == step);
+        }
+        best = best.max(size);
+    }
+    best
+}
+
+//the number of distinct colors in the (consistent) coloring in graph
+fn colors_used(graph: &[Node]) -> usize{
+    check_coloring(graph).num_colors
+}
+
+//search for the chromatic number with solve(graph, k) deciding whether k
+//colors are enough. A Colored answer that is not a valid coloring counts as
+//giving up, and so does NoSolution unless solve is complete, that is its
+//NoSolution is a proof. The best coloring found is left in the graph
+pub fn chromatic_number<F>(graph: &mut Vec<Node>, complete: bool, mut solve: F) -> ChromaticResult
+where F: FnMut(&mut Vec<Node>, usize) -> Outcome{
+    let now = Instant::now();
+    let greedy_colors = greedy::dsatur(graph);
+    let greedy_duration = now.elapsed();
+    let mut best: Vec<Option<Color>> = graph.iter().map(|node| node.color).collect();
+    let mut upper = greedy_colors;
+    let mut lower = greedy_clique(graph);
+    let mut attempts = Vec::new();
+
+    while upper > lower{
+        let num_colors = upper - 1;
+        let now = Instant::now();
+        let mut outcome = solve(graph, num_colors);
+        let duration = now.elapsed();
+        if outcome == Outcome::Colored && !check_coloring(graph).is_valid(){
+            outcome = Outcome::GaveUp;
+        }
+        if outcome == Outcome::NoSolution && !complete{
+            outcome = Outcome::GaveUp;
+        }
+        attempts.push(Attempt{num_colors, outcome, duration});
+        match outcome{
+            Outcome::Colored => {
+                upper = colors_used(graph);
+                best = graph.iter().map(|node| node.color).collect();
+            },
+            Outcome::NoSolution => lower = num_colors + 1,
+            Outcome::GaveUp => break,
+        }
+    }
+
+    for (node, color) in graph.iter_mut().zip(best){
+        node.color = color;
+    }
+    ChromaticResult{lower, upper, greedy_colors, greedy_duration, attempts}
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use crate::{all_consistent, exhaustive_search, generators};
+
+    fn exhaustive(graph: &mut [Node], num_colors: usize) -> Outcome{
+        match exhaustive_search(graph, num_colors){
+            true => Outcome::Colored,
+            false => Outcome::NoSolution,
+        }
+    }
+
+    #[test]
+    fn known_chromatic_numbers(){
+        let cases = vec![
+            (generators::mycielski(3), 4),
+            (generators::mycielski(4), 5),
+            (generators::queen_graph(5, 5), 5),
+            (generators::queen_graph(1, 6), 6),
+        ];
+        for (mut graph, chromatic) in cases{
+            let result = chromatic_number(&mut graph, true, |graph, k| exhaustive(graph, k));
+            assert!(result.is_exact());
+            assert_eq!(result.upper, chromatic);
+            assert!(result.greedy_colors >= chromatic);
+            assert!(all_consistent(&graph));
+            assert_eq!(colors_used(&graph), chromatic);
+        }
+    }
+
+    #[test]
+    fn attempts_walk_down_from_greedy(){
+        let mut graph = generators::mycielski(4);
+        let result = chromatic_number(&mut graph, true, |graph, k| exhaustive(graph, k));
+        let tried: Vec<usize> = result.attempts.iter().map(|attempt| attempt.num_colors).collect();
+        assert_eq!(*tried.last().unwrap(), 4);
+        assert_eq!(result.attempts.last().unwrap().outcome, Outcome::NoSolution);
+        assert!(tried.windows(2).all(|pair| pair[0] > pair[1]));
+    }
+
+    #[test]
+    fn bracket_when_solver_gives_up(){
+        let mut graph = generators::mycielski(4);
+        let result = chromatic_number(&mut graph, true, |_, _| Outcome::GaveUp);
+        assert!(!result.is_exact());
+        assert_eq!(result.lower, 2);//triangle free
+        assert_eq!(result.upper, result.greedy_colors);
+        assert_eq!(result.attempts.len(), 1);
+        assert!(all_consistent(&graph));
+    }
+
+    #[test]
+    fn incomplete_solver_only_brackets(){
+        //3-colorable by construction; a solver that cannot prove anything
+        //must not push the lower bound past the clique
+        let (mut graph, _) = generators::planted(30, 3, 0.3, &mut generators::Rng::new(1));
+        let clique = greedy_clique(&graph);
+        let result = chromatic_number(&mut graph, false, |_, _| Outcome::NoSolution);
+        assert_eq!(result.attempts.len(), 1);
+        assert_eq!(result.attempts[0].outcome, Outcome::GaveUp);
+        assert_eq!(result.lower, clique);
+        assert!(result.lower <= 3 && 3 <= result.upper);
+
+        let result = chromatic_number(&mut graph, true, |graph, k| exhaustive(graph, k));
+        assert!(result.is_exact());
+        assert_eq!(result.upper, 3);
+    }
+
+    #[test]
+    fn clique_bounds(){
+        assert_eq!(greedy_clique(&generators::queen_graph(1, 7)), 7);
+        assert_eq!(greedy_clique(&generators::queen_graph(4, 4)), 5);
+        assert_eq!(greedy_clique(&generators::mycielski(5)), 2);
+        assert_eq!(greedy_clique(&generators::mycielski(0)), 1);
+    }
+}
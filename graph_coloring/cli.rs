@@ -0,0 +1,302 @@
+//command line handling for the graph_coloring binary
+use crate::search::{Propagation, ValueOrder, VarOrder};
+
+pub const USAGE: &str = "usage: graph_coloring <graph.col|graph.col.b> [options]
+       graph_coloring --generate <spec> [options]
+options:
+    --generate <spec>                    use a built-in graph instead of a file: myciel<k>,
+                                         queen<r>_<c>, gnp:<n>:<p>, geometric:<n>:<radius>
+                                         or planted:<n>:<k>:<p>
+    --seed <s>                           seed for the random generators and the DBA
+                                         starting coloring (default 1)
+    --alg <exhaustive|exhaustive-par|abt-seq|abt-par|abt-do|dba|dsatur|welsh-powell|smallest-last>
+                                         algorithm to run (default abt-seq); abt-do is
+                                         ABT with dynamic agent ordering, exhaustive-par
+                                         splits the exhaustive search over threads
+    --var-order <index|max-degree|dsatur|degeneracy>
+                                         vertex order for exhaustive (default index)
+    --vertex-order <file>                vertex order for exhaustive given as 1-based vertex
+                                         numbers, one of each
+    --value-order <last-first|least-constraining|random>
+                                         color order for exhaustive (default last-first);
+                                         random uses the seed
+    --propagation <none|fc|ac>           after each color, forward check (fc) or also keep
+                                         arc consistency (ac) in exhaustive (default none)
+    --backjump                           let exhaustive jump back to the vertex that caused
+                                         a dead end instead of the one colored just before
+    --break-symmetry                     only search one of the colorings that differ by a
+                                         renaming of the colors (exhaustive, exhaustive-par
+                                         and abt-do)
+    --colors <k>                         number of colors (default 3, the greedy
+                                         colorers pick their own)
+    --threads <n>                        threads for abt-par and exhaustive-par (default 8)
+    --max-rounds <n>                     give up ABT or DBA after n rounds
+                                         (default: no limit for ABT, 10000 for DBA)
+    --chromatic                          search for the chromatic number, using the
+                                         algorithm for each number of colors
+    --quiet                              do not print the coloring
+    --output <file>                      write the coloring as \"vertex color\" lines
+    --canonical                          renumber the colors of the coloring by first use
+    --verify <file>                      check the coloring in file instead of coloring the graph
+    --convert <file>                     write the graph to file (.col or .col.b) instead of coloring it";
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Algorithm{
+    Exhaustive,
+    ParallelExhaustive,
+    AbtSequential,
+    AbtParallel,
+    AbtDo,
+    Dba,
+    Dsatur,
+    WelshPowell,
+    SmallestLast,
+}
+
+//where the graph comes from
+#[derive(PartialEq, Eq, Debug)]
+pub enum Input{
+    File(String),
+    Generate(String),
+}
+
+#[derive(Debug)]
+pub struct Config{
+    pub input: Input,
+    pub seed: u64,
+    pub algorithm: Algorithm,
+    pub num_colors: usize,
+    pub num_threads: usize,
+    pub max_rounds: Option<usize>,
+    pub var_order: VarOrder,
+    pub vertex_order: Option<String>,
+    pub value_order: ValueOrder,
+    pub propagation: Propagation,
+    pub backjumping: bool,
+    pub break_symmetry: bool,
+    pub canonical: bool,
+    pub chromatic: bool,
+    pub quiet: bool,
+    pub output: Option<String>,
+    pub verify: Option<String>,
+    pub convert_to: Option<String>,
+}
+
+fn parse_algorithm(name: &str) -> Result<Algorithm, String>{
+    match name{
+        "exhaustive" => Ok(Algorithm::Exhaustive),
+        "exhaustive-par" => Ok(Algorithm::ParallelExhaustive),
+        "abt-seq" => Ok(Algorithm::AbtSequential),
+        "abt-par" => Ok(Algorithm::AbtParallel),
+        "abt-do" => Ok(Algorithm::AbtDo),
+        "dba" => Ok(Algorithm::Dba),
+        "dsatur" => Ok(Algorithm::Dsatur),
+        "welsh-powell" => Ok(Algorithm::WelshPowell),
+        "smallest-last" => Ok(Algorithm::SmallestLast),
+        _ => Err(format!("unknown algorithm: {}", name)),
+    }
+}
+
+fn parse_var_order(name: &str) -> Result<VarOrder, String>{
+    match name{
+        "index" => Ok(VarOrder::Index),
+        "max-degree" => Ok(VarOrder::MaxDegree),
+        "dsatur" => Ok(VarOrder::Dsatur),
+        "degeneracy" => Ok(VarOrder::Degeneracy),
+        _ => Err(format!("unknown variable order: {}", name)),
+    }
+}
+
+fn parse_value_order(name: &str) -> Result<ValueOrder, String>{
+    match name{
+        "last-first" => Ok(ValueOrder::LastFirst),
+        "least-constraining" => Ok(ValueOrder::LeastConstraining),
+        "random" => Ok(ValueOrder::Random),
+        _ => Err(format!("unknown value order: {}", name)),
+    }
+}
+
+fn parse_propagation(name: &str) -> Result<Propagation, String>{
+    match name{
+        "none" => Ok(Propagation::None),
+        "fc" => Ok(Propagation::ForwardChecking),
+        "ac" => Ok(Propagation::ArcConsistency),
+        _ => Err(format!("unknown propagation: {}", name)),
+    }
+}
+
+fn parse_count(flag: &str, value: Option<&String>) -> Result<usize, String>{
+    let value = value.ok_or(format!("{} needs a value", flag))?;
+    match value.parse::<usize>(){
+        Ok(count) if count > 0 => Ok(count),
+        _ => Err(format!("{} expects a positive integer, got {}", flag, value)),
+    }
+}
+
+//args should not include the program name
+pub fn parse_args(args: &[String]) -> Result<Config, String>{
+    let mut input = None;
+    let mut config = Config{
+        input: Input::File(String::new()),
+        seed: 1,
+        algorithm: Algorithm::AbtSequential,
+        num_colors: 3,
+        num_threads: 8,
+        max_rounds: None,
+        var_order: VarOrder::Index,
+        vertex_order: None,
+        value_order: ValueOrder::LastFirst,
+        propagation: Propagation::None,
+        backjumping: false,
+        break_symmetry: false,
+        canonical: false,
+        chromatic: false,
+        quiet: false,
+        output: None,
+        verify: None,
+        convert_to: None,
+    };
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next(){
+        match arg.as_str(){
+            "--alg" => {
+                let name = iter.next().ok_or("--alg needs a value")?;
+                config.algorithm = parse_algorithm(name)?;
+            },
+            "--colors" => config.num_colors = parse_count(arg, iter.next())?,
+            "--threads" => config.num_threads = parse_count(arg, iter.next())?,
+            "--max-rounds" => config.max_rounds = Some(parse_count(arg, iter.next())?),
+            "--generate" => {
+                let spec = iter.next().ok_or("--generate needs a value")?;
+                if input.is_some(){
+                    return Err(format!("unexpected argument: {}", spec));
+                }
+                input = Some(Input::Generate(spec.clone()));
+            },
+            "--seed" => {
+                let seed = iter.next().ok_or("--seed needs a value")?;
+                config.seed = seed.parse().map_err(|_| format!("--seed expects an integer, got {}", seed))?;
+            },
+            "--var-order" => {
+                let name = iter.next().ok_or("--var-order needs a value")?;
+                config.var_order = parse_var_order(name)?;
+            },
+            "--vertex-order" => {
+                let file = iter.next().ok_or("--vertex-order needs a value")?;
+                config.vertex_order = Some(file.clone());
+            },
+            "--value-order" => {
+                let name = iter.next().ok_or("--value-order needs a value")?;
+                config.value_order = parse_value_order(name)?;
+            },
+            "--propagation" => {
+                let name = iter.next().ok_or("--propagation needs a value")?;
+                config.propagation = parse_propagation(name)?;
+            },
+            "--backjump" => config.backjumping = true,
+            "--break-symmetry" => config.break_symmetry = true,
+            "--canonical" => config.canonical = true,
+            "--chromatic" => config.chromatic = true,
+            "--quiet" => config.quiet = true,
+            "--output" => {
+                let file = iter.next().ok_or("--output needs a value")?;
+                config.output = Some(file.clone());
+            },
+            "--verify" => {
+                let file = iter.next().ok_or("--verify needs a value")?;
+                config.verify = Some(file.clone());
+            },
+            "--convert" => {
+                let file = iter.next().ok_or("--convert needs a value")?;
+                config.convert_to = Some(file.clone());
+            },
+            _ if arg.starts_with("--") => return Err(format!("unknown option: {}", arg)),
+            _ => {
+                if input.is_some(){
+                    return Err(format!("unexpected argument: {}", arg));
+                }
+                input = Some(Input::File(arg.clone()));
+            },
+        }
+    }
+
+    config.input = input.ok_or("missing graph file")?;
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    fn args(line: &str) -> Vec<String>{
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn parse_defaults(){
+        let config = parse_args(&args("g.col")).unwrap();
+        assert_eq!(config.input, Input::File("g.col".to_string()));
+        assert_eq!(config.algorithm, Algorithm::AbtSequential);
+        assert_eq!(config.num_colors, 3);
+        assert_eq!(config.max_rounds, None);
+        assert!(!config.chromatic);
+    }
+
+    #[test]
+    fn parse_options(){
+        let config = parse_args(&args("--alg abt-par --colors 9 --threads 4 g.col --max-rounds 100")).unwrap();
+        assert_eq!(config.algorithm, Algorithm::AbtParallel);
+        assert_eq!(config.num_colors, 9);
+        assert_eq!(config.num_threads, 4);
+        assert_eq!(config.max_rounds, Some(100));
+        assert_eq!(config.convert_to, None);
+
+        let config = parse_args(&args("g.col --alg abt-do")).unwrap();
+        assert_eq!(config.algorithm, Algorithm::AbtDo);
+        let config = parse_args(&args("g.col --alg exhaustive-par --threads 2")).unwrap();
+        assert_eq!(config.algorithm, Algorithm::ParallelExhaustive);
+
+        let config = parse_args(&args("g.col --alg exhaustive --var-order dsatur --value-order random")).unwrap();
+        assert_eq!(config.var_order, VarOrder::Dsatur);
+        assert_eq!(config.value_order, ValueOrder::Random);
+        assert_eq!(config.propagation, Propagation::None);
+        assert!(!config.backjumping);
+        let config = parse_args(&args("g.col --alg exhaustive --propagation ac --backjump")).unwrap();
+        assert_eq!(config.propagation, Propagation::ArcConsistency);
+        assert!(config.backjumping);
+        assert!(!config.break_symmetry && !config.canonical);
+        let config = parse_args(&args("g.col --alg abt-do --break-symmetry --canonical")).unwrap();
+        assert!(config.break_symmetry && config.canonical);
+        let config = parse_args(&args("g.col --vertex-order g.order")).unwrap();
+        assert_eq!(config.vertex_order, Some("g.order".to_string()));
+
+        let config = parse_args(&args("g.col --convert g.col.b")).unwrap();
+        assert_eq!(config.convert_to, Some("g.col.b".to_string()));
+
+        let config = parse_args(&args("g.col --output g.sol --verify other.sol")).unwrap();
+        assert_eq!(config.output, Some("g.sol".to_string()));
+        assert_eq!(config.verify, Some("other.sol".to_string()));
+
+        let config = parse_args(&args("--generate gnp:10:0.5 --seed 42")).unwrap();
+        assert_eq!(config.input, Input::Generate("gnp:10:0.5".to_string()));
+        assert_eq!(config.seed, 42);
+
+        let config = parse_args(&args("g.col --chromatic --alg exhaustive")).unwrap();
+        assert!(config.chromatic);
+    }
+
+    #[test]
+    fn parse_errors(){
+        assert!(parse_args(&args("")).is_err());
+        assert!(parse_args(&args("g.col --colors 0")).is_err());
+        assert!(parse_args(&args("g.col --colors")).is_err());
+        assert!(parse_args(&args("g.col --alg dfs")).is_err());
+        assert!(parse_args(&args("g.col h.col")).is_err());
+        assert!(parse_args(&args("g.col --generate myciel3")).is_err());
+        assert!(parse_args(&args("--generate myciel3 --seed x")).is_err());
+        assert!(parse_args(&args("g.col --var-order random")).is_err());
+        assert!(parse_args(&args("g.col --value-order dsatur")).is_err());
+        assert!(parse_args(&args("g.col --propagation mac")).is_err());
+    }
+}
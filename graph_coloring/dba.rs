@@ -0,0 +1,165 @@
+//Distributed Breakout Algorithm (Yokoo & Hirayama), simulated in synchronous
+//rounds like abt_sequential. Every round has an ok phase, where each node
+//learns its neighbors' colors through its agent_view, and an improve phase,
+//where each node works out how much it could lower its weighted conflicts and
+//only the nodes that beat all their neighbors get to move. A node stuck in a
+//quasi-local-minimum raises the weights of its violated edges instead.
+use crate::generators::Rng;
+use crate::{reset_graph, Color, Node, Outcome};
+
+//round budget when the caller does not give one; DBA cannot prove that a
+//graph is uncolorable, so it always needs one
+pub const DEFAULT_ROUNDS: usize = 10_000;
+
+pub struct DbaResult{
+    pub outcome: Outcome,//Colored or GaveUp
+    pub rounds: usize,
+    pub conflicts: usize,//conflicting edges in the coloring left in the graph
+}
+
+//number of edges whose ends share a color, each edge counted once
+fn count_conflicts(graph: &[Node]) -> usize{
+    let mut conflicts = 0;
+    for (index, node) in graph.iter().enumerate(){
+        for neighbor in &node.neighbors{
+            if *neighbor > index && graph[*neighbor].color == node.color{
+                conflicts += 1;
+            }
+        }
+    }
+    conflicts
+}
+
+//weighted conflicts of color at node according to its agent_view
+fn eval(node: &Node, weights: &[usize], color: Color) -> usize{
+    node.agent_view.iter().zip(weights)
+        .filter(|((_, neighbor_color), _)| *neighbor_color == color)
+        .map(|(_, weight)| *weight)
+        .sum()
+}
+
+//colors the graph with DBA, starting from a random coloring drawn from seed.
+//if no coloring is found within max_rounds the best coloring seen is left in
+//the graph
+pub fn dba(graph: &mut [Node], num_colors: usize, max_rounds: Option<usize>, seed: u64) -> DbaResult{
+    reset_graph(graph, num_colors);
+    let max_rounds = max_rounds.unwrap_or(DEFAULT_ROUNDS);
+    //the generators take the same seed, so use another stream than theirs or
+    //the start could simply be a planted coloring
+    let mut rng = Rng::new(!seed);
+    for node in graph.iter_mut(){
+        node.assign_color(Color(rng.below(num_colors.max(1))));
+    }
+    //weights[i][k] is node i's weight for the edge to graph[i].neighbors[k]
+    let mut weights: Vec<Vec<usize>> = graph.iter().map(|node| vec![1; node.neighbors.len()]).collect();
+    for node in graph.iter_mut(){
+        node.agent_view = node.neighbors.iter().map(|neighbor| (*neighbor, Color(0))).collect();
+    }
+
+    let mut best_conflicts = count_conflicts(graph);
+    let mut best_coloring: Vec<Option<Color>> = graph.iter().map(|node| node.color).collect();
+    let mut rounds = 0;
+    while best_conflicts > 0 && rounds < max_rounds{
+        rounds += 1;
+
+        //ok phase: every node hears its neighbors' current colors
+        for index in 0..graph.len(){
+            for k in 0..graph[index].neighbors.len(){
+                let neighbor = graph[index].neighbors[k];
+                graph[index].agent_view[k].1 = graph[neighbor].color.unwrap();
+            }
+        }
+
+        //improve phase: what every node could gain by switching color
+        let mut evals = Vec::with_capacity(graph.len());
+        let mut improves = Vec::with_capacity(graph.len());
+        let mut best_colors = Vec::with_capacity(graph.len());
+        for (index, node) in graph.iter().enumerate(){
+            let current = eval(node, &weights[index], node.color.unwrap());
+            let mut best_color = node.color.unwrap();
+            let mut best = current;
+            for color in Color::vector_of_colors(num_colors){
+                let value = eval(node, &weights[index], color);
+                if value < best{
+                    best = value;
+                    best_color = color;
+                }
+            }
+            evals.push(current);
+            improves.push(current - best);
+            best_colors.push(best_color);
+        }
+
+        //a node moves only if its improve beats every neighbor's (ties go
+        //to the smaller index); a stuck node with stuck neighbors is in a
+        //quasi-local-minimum and breaks out by raising its weights
+        for index in 0..graph.len(){
+            let improve = improves[index];
+            let neighbors = &graph[index].neighbors;
+            if improve > 0{
+                let wins = neighbors.iter().all(|neighbor| {
+                    improve > improves[*neighbor] || (improve == improves[*neighbor] && index < *neighbor)
+                });
+                if wins{
+                    graph[index].assign_color(best_colors[index]);
+                }
+            }else if evals[index] > 0 && neighbors.iter().all(|neighbor| improves[*neighbor] == 0){
+                let color = graph[index].color.unwrap();
+                for (k, (_, neighbor_color)) in graph[index].agent_view.iter().enumerate(){
+                    if *neighbor_color == color{
+                        weights[index][k] += 1;
+                    }
+                }
+            }
+        }
+
+        let conflicts = count_conflicts(graph);
+        if conflicts < best_conflicts{
+            best_conflicts = conflicts;
+            best_coloring = graph.iter().map(|node| node.color).collect();
+        }
+    }
+
+    for (node, color) in graph.iter_mut().zip(best_coloring){
+        node.color = color;
+    }
+    DbaResult{
+        outcome: if best_conflicts == 0 {Outcome::Colored} else {Outcome::GaveUp},
+        rounds,
+        conflicts: best_conflicts,
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use crate::{all_consistent, check_coloring, generators};
+
+    #[test]
+    fn dba_colors_planted_graph(){
+        let (mut graph, _) = generators::planted(150, 4, 0.3, &mut Rng::new(11));
+        let result = dba(&mut graph, 4, None, 1);
+        assert_eq!(result.outcome, Outcome::Colored);
+        assert_eq!(result.conflicts, 0);
+        assert!(all_consistent(&graph));
+    }
+
+    #[test]
+    fn dba_colors_mycielski(){
+        let mut graph = generators::mycielski(4);
+        let result = dba(&mut graph, 5, None, 3);
+        assert_eq!(result.outcome, Outcome::Colored);
+        assert!(all_consistent(&graph));
+    }
+
+    #[test]
+    fn dba_gives_up_with_best_effort(){
+        //K5 with 4 colors: the best coloring has one conflicting edge
+        let mut graph = generators::queen_graph(1, 5);
+        let result = dba(&mut graph, 4, Some(200), 5);
+        assert_eq!(result.outcome, Outcome::GaveUp);
+        assert_eq!(result.rounds, 200);
+        assert_eq!(result.conflicts, 1);
+        assert_eq!(check_coloring(&graph).conflicts.len(), 1);
+    }
+}
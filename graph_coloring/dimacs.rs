@@ -0,0 +1,276 @@
+//readers and writers for graphs in the DIMACS .col text format:
+//  c <comment>
+//  p edge <num_nodes> <num_edges>     ("p col" is accepted as well)
+//  e <u> <v>                          1-based vertex ids
+//and in the DIMACS binary .col.b format: the length of a text preamble on
+//its own line, the preamble (c and p lines), then the lower triangle of the
+//adjacency matrix, row i taking (i + 8) / 8 bytes with column j in bit
+//7 - j % 8 of byte j / 8
+use std::collections::HashSet;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use crate::Node;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError{
+    Io(String),
+    Line{line: usize, message: String},//line numbers start at 1
+}
+
+impl fmt::Display for ParseError{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result{
+        match self{
+            ParseError::Io(message) => write!(f, "{}", message),
+            ParseError::Line{line, message} => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+fn line_error(line: usize, message: String) -> ParseError{
+    ParseError::Line{line, message}
+}
+
+fn parse_number(field: Option<&str>, what: &str, line: usize) -> Result<usize, ParseError>{
+    match field{
+        None => Err(line_error(line, format!("missing {}", what))),
+        Some(text) => text.parse().map_err(|_| line_error(line, format!("invalid {}: {}", what, text))),
+    }
+}
+
+//1-based id from the file to a 0-based index into the graph
+fn parse_vertex(field: Option<&str>, num_nodes: usize, line: usize) -> Result<usize, ParseError>{
+    let id = parse_number(field, "vertex id", line)?;
+    if id == 0 || id > num_nodes{
+        return Err(line_error(line, format!("vertex {} out of range 1..={}", id, num_nodes)));
+    }
+    Ok(id - 1)
+}
+
+//parse a whole .col file; duplicate edges (in either direction) are kept once
+pub fn parse_graph<R: BufRead>(reader: R) -> Result<Vec<Node>, ParseError>{
+    let mut graph: Option<Vec<Node>> = None;
+    let mut edges = HashSet::new();
+    for (index, line) in reader.lines().enumerate(){
+        let line_no = index + 1;
+        let line = line.map_err(|err| ParseError::Io(format!("line {}: {}", line_no, err)))?;
+        let mut fields = line.split_whitespace();
+        match fields.next(){
+            None | Some("c") | Some("n") => continue,//blank, comment or node weight
+            Some("p") => {
+                if graph.is_some(){
+                    return Err(line_error(line_no, "more than one problem line".to_string()));
+                }
+                match fields.next(){
+                    Some("edge") | Some("edges") | Some("col") => (),
+                    Some(format) => return Err(line_error(line_no, format!("unsupported format: {}", format))),
+                    None => return Err(line_error(line_no, "missing format".to_string())),
+                }
+                let num_nodes = parse_number(fields.next(), "number of nodes", line_no)?;
+                parse_number(fields.next(), "number of edges", line_no)?;
+                graph = Some((0..num_nodes).map(Node::new).collect());
+            },
+            Some("e") => {
+                let graph = match graph.as_mut(){
+                    Some(graph) => graph,
+                    None => return Err(line_error(line_no, "edge before the problem line".to_string())),
+                };
+                let num_nodes = graph.len();
+                let source = parse_vertex(fields.next(), num_nodes, line_no)?;
+                let sink = parse_vertex(fields.next(), num_nodes, line_no)?;
+                if source == sink{
+                    return Err(line_error(line_no, format!("self-loop on vertex {}, the graph has no coloring", source + 1)));
+                }
+                if edges.insert((source.min(sink), source.max(sink))){
+                    graph[source].add_neighbor(sink);
+                    graph[sink].add_neighbor(source);
+                }
+            },
+            Some(kind) => return Err(line_error(line_no, format!("unknown line type: {}", kind))),
+        }
+    }
+    graph.ok_or_else(|| ParseError::Io("no problem line (p edge ...) found".to_string()))
+}
+
+//parse a whole .col.b file
+pub fn parse_binary_graph<R: BufRead>(mut reader: R) -> Result<Vec<Node>, ParseError>{
+    let io_error = |err: io::Error| ParseError::Io(format!("truncated binary graph: {}", err));
+    let mut first_line = String::new();
+    reader.read_line(&mut first_line).map_err(io_error)?;
+    let preamble_len: usize = first_line.trim().parse()
+        .map_err(|_| ParseError::Io(format!("invalid preamble length: {}", first_line.trim())))?;
+    let mut preamble = vec![0u8; preamble_len];
+    reader.read_exact(&mut preamble).map_err(io_error)?;
+
+    //the preamble has the same c and p lines as a text file, just no edges
+    let mut graph = parse_graph(&preamble[..])?;
+    let mut row = Vec::new();
+    for i in 0..graph.len(){
+        row.resize((i + 8) / 8, 0);
+        reader.read_exact(&mut row).map_err(io_error)?;
+        if row[i / 8] & (0x80 >> (i % 8)) != 0{
+            return Err(ParseError::Io(format!("self-loop on vertex {}, the graph has no coloring", i + 1)));
+        }
+        for j in 0..i{
+            if row[j / 8] & (0x80 >> (j % 8)) != 0{
+                graph[i].add_neighbor(j);
+                graph[j].add_neighbor(i);
+            }
+        }
+    }
+    Ok(graph)
+}
+
+fn is_binary_file(filename: &str) -> bool{
+    filename.ends_with(".b")
+}
+
+//reads the binary format if the file name ends in .b, the text format otherwise
+pub fn read_graph(filename: &str) -> Result<Vec<Node>, ParseError>{
+    let file = File::open(filename).map_err(|err| ParseError::Io(format!("{}: {}", filename, err)))?;
+    if is_binary_file(filename){
+        parse_binary_graph(BufReader::new(file))
+    }else{
+        parse_graph(BufReader::new(file))
+    }
+}
+
+//every edge once as (smaller index, larger index), sorted
+fn edge_list(graph: &[Node]) -> Vec<(usize, usize)>{
+    let mut edges = HashSet::new();
+    for (index, node) in graph.iter().enumerate(){
+        for neighbor in &node.neighbors{
+            edges.insert((index.min(*neighbor), index.max(*neighbor)));
+        }
+    }
+    let mut edges: Vec<(usize, usize)> = edges.into_iter().collect();
+    edges.sort_unstable();
+    edges
+}
+
+pub fn write_graph<W: Write>(graph: &[Node], mut writer: W) -> io::Result<()>{
+    let edges = edge_list(graph);
+    writeln!(writer, "p edge {} {}", graph.len(), edges.len())?;
+    for (source, sink) in edges{
+        writeln!(writer, "e {} {}", source + 1, sink + 1)?;
+    }
+    Ok(())
+}
+
+pub fn write_binary_graph<W: Write>(graph: &[Node], mut writer: W) -> io::Result<()>{
+    let edges = edge_list(graph);
+    let preamble = format!("p edge {} {}\n", graph.len(), edges.len());
+    write!(writer, "{}\n{}", preamble.len(), preamble)?;
+    let mut rows: Vec<Vec<u8>> = (0..graph.len()).map(|i| vec![0u8; (i + 8) / 8]).collect();
+    for (low, high) in edges{
+        rows[high][low / 8] |= 0x80 >> (low % 8);
+    }
+    for row in rows{
+        writer.write_all(&row)?;
+    }
+    Ok(())
+}
+
+//writes the binary format if the file name ends in .b, the text format otherwise
+pub fn save_graph(graph: &[Node], filename: &str) -> io::Result<()>{
+    let mut writer = BufWriter::new(File::create(filename)?);
+    if is_binary_file(filename){
+        write_binary_graph(graph, &mut writer)?;
+    }else{
+        write_graph(graph, &mut writer)?;
+    }
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use crate::sorted_neighbors;
+
+    fn parse(text: &str) -> Result<Vec<Node>, ParseError>{
+        parse_graph(text.as_bytes())
+    }
+
+    fn error_line(text: &str) -> usize{
+        match parse(text){
+            Err(ParseError::Line{line, ..}) => line,
+            other => panic!("expected a line error, got {:?}", other.map(|graph| graph.len())),
+        }
+    }
+
+    #[test]
+    fn parse_triangle(){
+        let graph = parse("c a triangle\n\np col 3 3\ne 1 2\ne 2\t3\ne  3 1\n").unwrap();
+        assert_eq!(graph.len(), 3);
+        assert_eq!(graph[0].neighbors, vec![1, 2]);
+        assert_eq!(graph[1].neighbors, vec![0, 2]);
+        assert_eq!(graph[2].neighbors, vec![1, 0]);
+    }
+
+    #[test]
+    fn duplicate_edges(){
+        let graph = parse("p edge 2 3\ne 1 2\ne 2 1\ne 1 2\n").unwrap();
+        assert_eq!(graph[0].neighbors, vec![1]);
+        assert_eq!(graph[1].neighbors, vec![0]);
+    }
+
+    #[test]
+    fn line_errors(){
+        assert_eq!(error_line("c\ne 1 2\n"), 2);
+        assert_eq!(error_line("p edge 2 1\ne 1 x\n"), 2);
+        assert_eq!(error_line("p edge 2 1\ne 1\n"), 2);
+        assert_eq!(error_line("p edge 2 1\n\ne 1 3\n"), 3);
+        assert_eq!(error_line("p edge 2 1\ne 0 1\n"), 2);
+        assert_eq!(error_line("p edge 2 1\ne 2 2\n"), 2);
+        assert_eq!(error_line("p edge 2 1\np edge 2 1\n"), 2);
+        assert_eq!(error_line("p cnf 2 1\n"), 1);
+        assert_eq!(error_line("p edge 2 1\nq 1 2\n"), 2);
+    }
+
+    #[test]
+    fn missing_input(){
+        assert!(matches!(parse("c nothing here\n"), Err(ParseError::Io(_))));
+        assert!(matches!(read_graph("graph_coloring/no_such_file.col"), Err(ParseError::Io(_))));
+    }
+
+    #[test]
+    fn parse_binary_triangle(){
+        let mut bytes = b"11\np edge 3 3\n".to_vec();
+        bytes.extend_from_slice(&[0x00, 0x80, 0xc0]);
+        let graph = parse_binary_graph(&bytes[..]).unwrap();
+        assert_eq!(sorted_neighbors(&graph), vec![vec![1, 2], vec![0, 2], vec![0, 1]]);
+
+        let mut written = Vec::new();
+        write_binary_graph(&graph, &mut written).unwrap();
+        assert_eq!(written, bytes);
+    }
+
+    #[test]
+    fn binary_errors(){
+        assert!(parse_binary_graph(&b"x\n"[..]).is_err());
+        assert!(parse_binary_graph(&b"11\np edge 3 3\n\x00\x80"[..]).is_err());
+        assert!(parse_binary_graph(&b"11\np edge 1 1\n\x80"[..]).is_err());
+    }
+
+    #[test]
+    fn binary_matches_text(){
+        let graph = read_graph("graph_coloring/myciel5.sol").unwrap();
+        let mut bytes = Vec::new();
+        write_binary_graph(&graph, &mut bytes).unwrap();
+        let binary = parse_binary_graph(&bytes[..]).unwrap();
+        assert_eq!(sorted_neighbors(&binary), sorted_neighbors(&graph));
+
+        let mut text = Vec::new();
+        write_graph(&binary, &mut text).unwrap();
+        let reparsed = parse_graph(&text[..]).unwrap();
+        assert_eq!(sorted_neighbors(&reparsed), sorted_neighbors(&graph));
+    }
+
+    #[test]
+    fn myciel3_file(){
+        let graph = read_graph("graph_coloring/myciel3.sol").unwrap();
+        assert_eq!(graph.len(), 11);
+        let num_edges: usize = graph.iter().map(|node| node.neighbors.len()).sum();
+        assert_eq!(num_edges, 2 * 20);
+    }
+}
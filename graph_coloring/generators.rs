@@ -0,0 +1,239 @@
+//built-in graph families, so benchmarks do not depend on instance files
+use crate::{Color, Node};
+
+//small splitmix64 generator so runs can be repeated from a seed
+pub struct Rng{
+    state: u64,
+}
+
+impl Rng{
+    pub fn new(seed: u64) -> Rng{
+        Rng{state: seed}
+    }
+
+    pub fn next_u64(&mut self) -> u64{
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    //uniform in [0, 1)
+    pub fn next_f64(&mut self) -> f64{
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    //uniform in 0..bound
+    pub fn below(&mut self, bound: usize) -> usize{
+        (self.next_u64() % bound as u64) as usize
+    }
+}
+
+fn empty_graph(num_nodes: usize) -> Vec<Node>{
+    (0..num_nodes).map(Node::new).collect()
+}
+
+fn add_edge(graph: &mut [Node], source: usize, sink: usize){
+    graph[source].add_neighbor(sink);
+    graph[sink].add_neighbor(source);
+}
+
+//the Mycielski graph numbered as in the DIMACS myciel<order>.col files:
+//order 0 is a single vertex, order 1 is K2, order 2 is C5 and order 3 the
+//11-vertex Groetzsch graph. It is triangle free with chromatic number order + 1
+pub fn mycielski(order: usize) -> Vec<Node>{
+    let mut graph = empty_graph(1);
+    if order == 0{
+        return graph;
+    }
+    graph.push(Node::new(1));
+    add_edge(&mut graph, 0, 1);
+    for _ in 1..order{
+        //vertex i gets a shadow n + i adjacent to the neighbors of i, and all
+        //shadows are joined to one new vertex 2n
+        let n = graph.len();
+        let edges: Vec<(usize, usize)> = (0..n)
+            .flat_map(|i| graph[i].neighbors.iter().map(move |j| (i, *j)))
+            .collect();
+        for i in n..(2 * n + 1){
+            graph.push(Node::new(i));
+        }
+        for (i, j) in edges{
+            if i < j{
+                add_edge(&mut graph, i, n + j);
+                add_edge(&mut graph, j, n + i);
+            }
+        }
+        for i in n..(2 * n){
+            add_edge(&mut graph, i, 2 * n);
+        }
+    }
+    graph
+}
+
+//the DIMACS queen<rows>_<cols> graph: one vertex per square (row major), two
+//squares adjacent if a queen on one attacks the other
+pub fn queen_graph(rows: usize, cols: usize) -> Vec<Node>{
+    let mut graph = empty_graph(rows * cols);
+    for a in 0..(rows * cols){
+        let (ar, ac) = (a / cols, a % cols);
+        for b in (a + 1)..(rows * cols){
+            let (br, bc) = (b / cols, b % cols);
+            if ar == br || ac == bc || ar + bc == br + ac || ar + ac == br + bc{
+                add_edge(&mut graph, a, b);
+            }
+        }
+    }
+    graph
+}
+
+//Erdos-Renyi G(n, p): every pair is an edge independently with probability p
+pub fn gnp(num_nodes: usize, p: f64, rng: &mut Rng) -> Vec<Node>{
+    let mut graph = empty_graph(num_nodes);
+    for a in 0..num_nodes{
+        for b in (a + 1)..num_nodes{
+            if rng.next_f64() < p{
+                add_edge(&mut graph, a, b);
+            }
+        }
+    }
+    graph
+}
+
+//random geometric graph: points uniform in the unit square, adjacent when
+//they are at most radius apart
+pub fn geometric(num_nodes: usize, radius: f64, rng: &mut Rng) -> Vec<Node>{
+    let points: Vec<(f64, f64)> = (0..num_nodes).map(|_| (rng.next_f64(), rng.next_f64())).collect();
+    let mut graph = empty_graph(num_nodes);
+    for a in 0..num_nodes{
+        for b in (a + 1)..num_nodes{
+            let (dx, dy) = (points[a].0 - points[b].0, points[a].1 - points[b].1);
+            if dx * dx + dy * dy <= radius * radius{
+                add_edge(&mut graph, a, b);
+            }
+        }
+    }
+    graph
+}
+
+//a graph with a hidden num_colors coloring: vertices get random colors and
+//only differently colored pairs become edges, each with probability p.
+//returns the graph together with the planted coloring
+pub fn planted(num_nodes: usize, num_colors: usize, p: f64, rng: &mut Rng) -> (Vec<Node>, Vec<Color>){
+    let colors: Vec<Color> = (0..num_nodes).map(|_| Color(rng.below(num_colors.max(1)))).collect();
+    let mut graph = empty_graph(num_nodes);
+    for a in 0..num_nodes{
+        for b in (a + 1)..num_nodes{
+            if colors[a] != colors[b] && rng.next_f64() < p{
+                add_edge(&mut graph, a, b);
+            }
+        }
+    }
+    (graph, colors)
+}
+
+fn spec_number<T: std::str::FromStr>(field: Option<&str>, spec: &str) -> Result<T, String>{
+    field.and_then(|text| text.parse().ok()).ok_or(format!("bad generator spec: {}", spec))
+}
+
+//build a graph from a spec such as myciel5, queen8_8, gnp:100:0.1,
+//geometric:100:0.2 or planted:100:4:0.3
+pub fn from_spec(spec: &str, seed: u64) -> Result<Vec<Node>, String>{
+    let mut rng = Rng::new(seed);
+    let mut fields = spec.split(':');
+    let kind = fields.next().unwrap_or("");
+    let graph = if let Some(order) = kind.strip_prefix("myciel"){
+        mycielski(spec_number(Some(order), spec)?)
+    }else if let Some(size) = kind.strip_prefix("queen"){
+        let mut dims = size.split('_');
+        let rows = spec_number(dims.next(), spec)?;
+        let cols = spec_number(dims.next(), spec)?;
+        queen_graph(rows, cols)
+    }else{
+        match kind{
+            "gnp" => gnp(spec_number(fields.next(), spec)?, spec_number(fields.next(), spec)?, &mut rng),
+            "geometric" => geometric(spec_number(fields.next(), spec)?, spec_number(fields.next(), spec)?, &mut rng),
+            "planted" => {
+                let num_nodes = spec_number(fields.next(), spec)?;
+                let num_colors = spec_number(fields.next(), spec)?;
+                planted(num_nodes, num_colors, spec_number(fields.next(), spec)?, &mut rng).0
+            },
+            _ => return Err(format!("unknown generator: {}", spec)),
+        }
+    };
+    if fields.next().is_some(){
+        return Err(format!("bad generator spec: {}", spec));
+    }
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use crate::{dimacs, exhaustive_search, all_consistent, sorted_neighbors};
+
+    fn num_edges(graph: &[Node]) -> usize{
+        graph.iter().map(|node| node.neighbors.len()).sum::<usize>() / 2
+    }
+
+    #[test]
+    fn mycielski_matches_dimacs_files(){
+        for order in 3..8{
+            let file = dimacs::read_graph(&format!("graph_coloring/myciel{}.sol", order)).unwrap();
+            assert_eq!(sorted_neighbors(&mycielski(order)), sorted_neighbors(&file));
+        }
+    }
+
+    #[test]
+    fn mycielski_chromatic_number(){
+        for order in 0..5{
+            let mut graph = mycielski(order);
+            assert!(exhaustive_search(&mut graph, order + 1));
+            if order > 0{
+                assert!(!exhaustive_search(&mut graph, order));
+            }
+        }
+    }
+
+    #[test]
+    fn queen_graph_edges(){
+        let graph = queen_graph(5, 5);
+        assert_eq!(graph.len(), 25);
+        assert_eq!(num_edges(&graph), 160);
+        assert_eq!(num_edges(&queen_graph(1, 4)), 6);
+    }
+
+    #[test]
+    fn random_graphs_repeat_from_seed(){
+        let a = gnp(50, 0.2, &mut Rng::new(7));
+        let b = gnp(50, 0.2, &mut Rng::new(7));
+        assert_eq!(sorted_neighbors(&a), sorted_neighbors(&b));
+        assert_eq!(num_edges(&gnp(30, 0.0, &mut Rng::new(1))), 0);
+        assert_eq!(num_edges(&gnp(30, 1.0, &mut Rng::new(1))), 30 * 29 / 2);
+        assert_eq!(num_edges(&geometric(30, 2.0, &mut Rng::new(1))), 30 * 29 / 2);
+    }
+
+    #[test]
+    fn planted_coloring_is_proper(){
+        let (mut graph, colors) = planted(60, 4, 0.5, &mut Rng::new(3));
+        for (node, color) in graph.iter_mut().zip(&colors){
+            node.assign_color(*color);
+        }
+        assert!(all_consistent(&graph));
+        assert!(exhaustive_search(&mut graph, 4));
+    }
+
+    #[test]
+    fn specs(){
+        assert_eq!(from_spec("myciel4", 1).unwrap().len(), 23);
+        assert_eq!(from_spec("queen6_4", 1).unwrap().len(), 24);
+        assert_eq!(from_spec("gnp:40:0.5", 1).unwrap().len(), 40);
+        assert_eq!(from_spec("geometric:40:0.3", 1).unwrap().len(), 40);
+        assert_eq!(from_spec("planted:40:3:0.5", 1).unwrap().len(), 40);
+        assert!(from_spec("queen6", 1).is_err());
+        assert!(from_spec("gnp:40", 1).is_err());
+        assert!(from_spec("gnp:40:0.5:1", 1).is_err());
+        assert!(from_spec("petersen", 1).is_err());
+    }
+}
@@ -0,0 +1,181 @@
+//greedy colorings that give fast upper bounds on the number of colors.
+//each one leaves a complete, consistent coloring in the graph and returns
+//the number of colors it used
+use std::collections::BTreeSet;
+use std::cmp::Reverse;
+use crate::{Color, Node};
+
+//give every node in order the smallest color none of its colored neighbors has
+fn first_fit(graph: &mut [Node], order: &[usize]) -> usize{
+    for node in graph.iter_mut(){
+        node.color = None;
+    }
+    //used[c] == stamp marks color c as taken for the current node
+    let mut used: Vec<usize> = Vec::new();
+    let mut num_colors = 0;
+    for (stamp, &index) in order.iter().enumerate(){
+        for neighbor in &graph[index].neighbors{
+            if let Some(Color(c)) = graph[*neighbor].color{
+                if used.len() <= c{
+                    used.resize(c + 1, usize::MAX);
+                }
+                used[c] = stamp;
+            }
+        }
+        let color = (0..).find(|c| used.get(*c) != Some(&stamp)).unwrap();
+        graph[index].assign_color(Color(color));
+        num_colors = num_colors.max(color + 1);
+    }
+    num_colors
+}
+
+//Welsh-Powell: first fit with the nodes by decreasing degree
+pub fn welsh_powell(graph: &mut [Node]) -> usize{
+    let mut order: Vec<usize> = (0..graph.len()).collect();
+    order.sort_by_key(|index| Reverse(graph[*index].neighbors.len()));
+    first_fit(graph, &order)
+}
+
+//smallest-last (Matula-Beck): repeatedly take out a node of smallest degree
+//in what is left of the graph, then first fit in the reverse order. Uses at
+//most degeneracy + 1 colors
+pub fn smallest_last(graph: &mut [Node]) -> usize{
+    let order = smallest_last_order(graph);
+    first_fit(graph, &order)
+}
+
+//the smallest-last vertex order, computed with degree buckets
+pub fn smallest_last_order(graph: &[Node]) -> Vec<usize>{
+    let num_nodes = graph.len();
+    let mut degree: Vec<usize> = graph.iter().map(|node| node.neighbors.len()).collect();
+    let max_degree = degree.iter().copied().max().unwrap_or(0);
+    //buckets[d] holds nodes that had degree d when pushed; stale entries are skipped
+    let mut buckets: Vec<Vec<usize>> = vec![Vec::new(); max_degree + 1];
+    for (index, d) in degree.iter().enumerate(){
+        buckets[*d].push(index);
+    }
+    let mut removed = vec![false; num_nodes];
+    let mut order = Vec::with_capacity(num_nodes);
+    let mut low = 0;
+    while order.len() < num_nodes{
+        let index = match buckets[low].pop(){
+            Some(index) => index,
+            None => {
+                low += 1;
+                continue;
+            },
+        };
+        if removed[index] || degree[index] != low{
+            continue;
+        }
+        removed[index] = true;
+        order.push(index);
+        for neighbor in &graph[index].neighbors{
+            if !removed[*neighbor]{
+                degree[*neighbor] -= 1;
+                buckets[degree[*neighbor]].push(*neighbor);
+                low = low.min(degree[*neighbor]);
+            }
+        }
+    }
+    order.reverse();
+    order
+}
+
+//DSATUR (Brelaz): always color the uncolored node with the most distinct
+//colors among its neighbors, ties going to the larger degree
+pub fn dsatur(graph: &mut [Node]) -> usize{
+    for node in graph.iter_mut(){
+        node.color = None;
+    }
+    let num_nodes = graph.len();
+    let mut neighbor_colors: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); num_nodes];
+    //(saturation, degree, smallest index first) of every uncolored node
+    let mut queue: BTreeSet<(usize, usize, Reverse<usize>)> = (0..num_nodes)
+        .map(|index| (0, graph[index].neighbors.len(), Reverse(index)))
+        .collect();
+    let mut num_colors = 0;
+    while let Some((_, _, Reverse(index))) = queue.pop_last(){
+        let taken = &neighbor_colors[index];
+        let color = (0..).find(|c| !taken.contains(c)).unwrap();
+        graph[index].assign_color(Color(color));
+        num_colors = num_colors.max(color + 1);
+        for k in 0..graph[index].neighbors.len(){
+            let neighbor = graph[index].neighbors[k];
+            if graph[neighbor].color.is_some(){
+                continue;
+            }
+            let degree = graph[neighbor].neighbors.len();
+            let saturation = neighbor_colors[neighbor].len();
+            if neighbor_colors[neighbor].insert(color){
+                queue.remove(&(saturation, degree, Reverse(neighbor)));
+                queue.insert((saturation + 1, degree, Reverse(neighbor)));
+            }
+        }
+    }
+    num_colors
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use crate::{all_consistent, generators};
+    use crate::generators::Rng;
+
+    fn cycle(num_nodes: usize) -> Vec<Node>{
+        let mut graph: Vec<Node> = (0..num_nodes).map(Node::new).collect();
+        for i in 0..num_nodes{
+            let j = (i + 1) % num_nodes;
+            graph[i].add_neighbor(j);
+            graph[j].add_neighbor(i);
+        }
+        graph
+    }
+
+    fn all_greedy(graph: &mut [Node]) -> Vec<usize>{
+        let mut used = Vec::new();
+        for colorer in [dsatur, welsh_powell, smallest_last]{
+            used.push(colorer(graph));
+            assert!(all_consistent(graph));
+        }
+        used
+    }
+
+    #[test]
+    fn complete_graph(){
+        let mut graph = generators::queen_graph(1, 9);
+        assert_eq!(all_greedy(&mut graph), vec![9, 9, 9]);
+    }
+
+    #[test]
+    fn bipartite_and_odd_cycles(){
+        assert_eq!(dsatur(&mut cycle(10)), 2);
+        assert_eq!(dsatur(&mut cycle(11)), 3);
+        //smallest-last never needs more than degeneracy + 1 colors
+        assert!(smallest_last(&mut cycle(10)) <= 3);
+    }
+
+    #[test]
+    fn greedy_bounds(){
+        let mut graph = generators::mycielski(5);
+        for used in all_greedy(&mut graph){
+            assert!(used >= 6);
+        }
+        let (mut graph, _) = generators::planted(300, 5, 0.2, &mut Rng::new(2));
+        for used in all_greedy(&mut graph){
+            assert!(used >= 2);
+        }
+        let mut graph = generators::queen_graph(8, 8);
+        for used in all_greedy(&mut graph){
+            assert!(used >= 9);
+        }
+    }
+
+    #[test]
+    fn smallest_last_order_is_a_permutation(){
+        let graph = generators::gnp(100, 0.1, &mut Rng::new(4));
+        let mut order = smallest_last_order(&graph);
+        order.sort_unstable();
+        assert_eq!(order, (0..100).collect::<Vec<usize>>());
+    }
+}
@@ -0,0 +1,24 @@
+//a color is just an index in 0..k, where k is chosen at runtime
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash, PartialOrd, Ord)]
+pub struct Color(pub usize);
+
+const NAMES: [&str; 7] = ["Red", "Blue", "Green", "Yellow", "Black", "White", "Pink"];
+
+impl Color{
+    pub fn index(&self) -> usize{
+        self.0
+    }
+
+    //the colors 0..num_colors, in the order the solvers try them
+    pub fn vector_of_colors(num_colors: usize) -> Vec<Color>{
+        (0..num_colors).map(Color).collect()
+    }
+
+    //human readable name; colors past the named ones fall back to their index
+    pub fn name(&self) -> String{
+        match NAMES.get(self.0){
+            Some(name) => name.to_string(),
+            None => format!("Color{}", self.0),
+        }
+    }
+}
@@ -1,19 +1,31 @@
+use std::collections::BTreeSet;
 use std::thread;
 use std::sync::{Arc, Mutex, Barrier};
+use std::mem;
+use std::process;
 use std::time::Instant;
-use std::env;
-use std::fs::File;
-use std::io::{self,BufReader,BufRead};
-use std::path::Path;
-use std::str::FromStr;
-mod seven_coloring;
-pub use crate::seven_coloring::seven_coloring::*;
-
-#[derive(PartialEq)]
-enum State{
-    NoSol,
-    NoChange,
-    HasChange,
+mod k_coloring;
+mod cli;
+mod dimacs;
+mod solution;
+mod generators;
+mod dba;
+mod greedy;
+mod chromatic;
+mod abt_do;
+mod search;
+mod symmetry;
+mod parallel;
+pub use crate::k_coloring::*;
+use crate::cli::{Algorithm, Config, Input};
+use crate::search::{Propagation, SearchOptions, VarOrder};
+
+//how a solver run ended
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum Outcome{
+    Colored,
+    NoSolution,//proven uncolorable with the given number of colors
+    GaveUp,//ran out of rounds before reaching an answer
 }
 
 struct Node{
@@ -22,22 +34,28 @@ struct Node{
     neighbors: Vec<usize>,
     remaining: Vec<Color>,
     agent_view: Vec<NodeState>,
-    no_good: Vec<Color>,
+    no_good: Vec<Nogood>,
+    links: Vec<usize>,//lower priority nodes that are not neighbors but hold nogoods on our color
     modified: bool,//keep track of whether the current state has been modified by other nodes
                    //this is equivalent to indicating whether a new msg is received if we are working in message-passing
 }
 
 type NodeState = (usize, Color);
 
+//a color the node may not take while the higher priority nodes hold the
+//colors listed with it
+type Nogood = (Color, Vec<NodeState>);
+
 impl Node{
     fn new(name:usize) -> Node{
         Node{
-            name: name,
+            name,
             color: None,
             neighbors: Vec::new(),
-            remaining: Color::vector_of_colors(),
+            remaining: Vec::new(),
             agent_view: Vec::new(),
             no_good: Vec::new(),
+            links: Vec::new(),
             modified:false,
         }
     }
@@ -49,230 +67,537 @@ impl Node{
     fn assign_color(&mut self, color: Color){
         self.color = Some(color);
     }
+
+    //forget any previous run and give the node the full set of num_colors colors
+    fn reset(&mut self, num_colors: usize){
+        self.color = None;
+        self.remaining = Color::vector_of_colors(num_colors);
+        self.agent_view.clear();
+        self.no_good.clear();
+        self.links.clear();
+        self.modified = false;
+    }
 }
 
-//check if the coloring of the input (partial) graph is feasible
-//the function makes the assumption that the graph is consists of nodes: 1,2,...,graph.size()
-fn all_consistent(graph: &Vec<Node>)->bool{
-    for node in graph{
-        for neighbor in &node.neighbors{
-            if *neighbor < graph.len(){
-                if (graph[*neighbor].color).as_ref() == (node.color).as_ref(){
-                    return false;
-                }
-            }
-        }
+//prepare every node of the graph for a run with num_colors colors
+fn reset_graph(graph: &mut [Node], num_colors: usize){
+    for node in graph.iter_mut(){
+        node.reset(num_colors);
     }
-    true
 }
 
-//check if cur's assignment is valid
-//bound is the number of elements that have been assigned a color in graph
-//the fucntion assumes that the original graph is valid
-fn new_assign_valid(graph: &Vec<Node>, cur: &Node, bound: usize)->bool{
-    for neighbor in &cur.neighbors{
-        if *neighbor < bound{
-            if (graph[*neighbor].color).as_ref() == (cur.color).as_ref(){
-                return false;
-            }
-        }
+//every node's neighbors in increasing order, to compare graphs built in different orders
+#[cfg(test)]
+fn sorted_neighbors(graph: &[Node]) -> Vec<Vec<usize>>{
+    graph.iter().map(|node| {
+        let mut neighbors = node.neighbors.clone();
+        neighbors.sort_unstable();
+        neighbors
+    }).collect()
+}
+
+//everything wrong with a (partial) coloring
+struct ColoringReport{
+    conflicts: Vec<(usize, usize)>,//edges whose ends share a color, smaller index first
+    uncolored: Vec<usize>,
+    num_colors: usize,//number of distinct colors in use
+}
+
+impl ColoringReport{
+    fn is_valid(&self) -> bool{
+        self.conflicts.is_empty() && self.uncolored.is_empty()
     }
-    true
 }
 
-//single-thread exhaustive search: used as a reference for performance
-fn exhaustive_search(graph: &mut Vec<Node>)->bool{
-    let mut index = 0;
-    while index < graph.len(){
-        let mut has_match = false;
-        while graph[index].remaining.len() > 0{
-            let select = graph[index].remaining.pop().unwrap();
-            graph[index].assign_color(select);
-            if new_assign_valid(graph, &graph[index], index){
-                has_match = true;
-                break;
-            }
-        }
-        if has_match == false{
-            if index > 0{//backtrack
-                //reset color candidates for current vertex before backtracking
-                graph[index].remaining = Color::vector_of_colors();
-                index = index - 1;
-            }else{
-                return false;
+//find every conflicting edge and every uncolored vertex
+//the function makes the assumption that the graph is consists of nodes: 1,2,...,graph.size()
+fn check_coloring(graph: &[Node]) -> ColoringReport{
+    let mut conflicts = BTreeSet::new();
+    let mut uncolored = Vec::new();
+    let mut colors = BTreeSet::new();
+    for (index, node) in graph.iter().enumerate(){
+        let color = match node.color{
+            Some(color) => color,
+            None => {
+                uncolored.push(index);
+                continue;
+            },
+        };
+        colors.insert(color);
+        for neighbor in &node.neighbors{
+            if *neighbor < graph.len() && graph[*neighbor].color == Some(color){
+                conflicts.insert((index.min(*neighbor), index.max(*neighbor)));
             }
-        }else{//found a match
-            index = index + 1;
         }
     }
-    true
+    ColoringReport{
+        conflicts: conflicts.into_iter().collect(),
+        uncolored,
+        num_colors: colors.len(),
+    }
+}
+
+//check if the coloring of the input graph is complete and feasible
+fn all_consistent(graph: &[Node])->bool{
+    check_coloring(graph).is_valid()
+}
+
+//single-thread exhaustive search with the default orderings; the binary goes
+//through search::search with the orderings from the command line
+#[cfg(test)]
+fn exhaustive_search(graph: &mut [Node], num_colors: usize)->bool{
+    search::search(graph, num_colors, &SearchOptions::default()).outcome == Outcome::Colored
+}
+
+//whether color clashes with a neighbor in the agent view, or with a nogood
+//whose other colors all hold in the agent view
+fn ruled_out(node: &Node, color: Color) -> bool{
+    let clash = node.agent_view.iter().any(|(agent, agent_color)| {
+        *agent_color == color && node.neighbors.contains(agent)
+    });
+    clash || node.no_good.iter().any(|(nogood_color, context)| {
+        *nogood_color == color && context.iter().all(|state| node.agent_view.contains(state))
+    })
 }
 
 //update color of current node according to agent view
 //return the updated color (or None if no color is consistent)
 fn update_color(node: &Node) -> Option<Color>{
-    let mut valid = true;
-    if (node.color != None){//the node already has a color, check whether it is consistent
-        for (neighbor_name, neighbor_color) in &node.agent_view{
-            if node.color.unwrap() == *neighbor_color{
-                valid = false;
-            }
-        }
-        for node_color in &node.no_good{
-            if *node_color == node.color.unwrap(){
-                valid = false;
-            }
+    if let Some(color) = node.color{//the node already has a color, keep it if it is consistent
+        if !ruled_out(node, color){
+            return Some(color);
         }
-    }else{
-        valid = false;
     }
-    if valid{//no need for a new color
-        return node.color;
+    node.remaining.iter().copied().find(|color| !ruled_out(node, *color))
+}
+
+//one ABT step for node_index: pick a consistent color and tell the higher
+//neighbors about it, or send a nogood to the lowest priority agent in the view.
+//returns None if there is no solution and nowhere to backtrack, otherwise
+//whether the node changed its state
+fn abt_step(graph: &mut [Node], node_index: usize) -> Option<bool>{
+    let mut has_change = false;
+
+    //first determine a value
+    let (last_color, next_color) = get_last_and_next_color(graph, node_index);
+    if let Some(color) = next_color{
+        graph[node_index].assign_color(color);
+    }
+    match (last_color, next_color){
+        (Some(last), Some(next)) => if last != next{
+            has_change = true;
+        },
+        _ => has_change = true,
     }
+    graph[node_index].modified = true;
+    match next_color{
+        //find a value
+        Some(color) => {
+            //color is updated, update agent view
+            if last_color.is_none() || last_color == Some(color){
+                let lower: Vec<usize> = graph[node_index].neighbors.iter().copied()
+                    .filter(|neighbor| graph[*neighbor].name > node_index)
+                    .chain(graph[node_index].links.iter().copied()).collect();
+                for neighbor in lower{
+                    let mut addition = true;
+                    for agent_index in 0..graph[neighbor].agent_view.len(){
+                        let (neighbor_name, _) = graph[neighbor].agent_view[agent_index];
+                        if neighbor_name == node_index{//agent view of neighbor already contains current node, just need to update value
+                            //a view that changes is still work to do, the neighbor has to look at it
+                            if graph[neighbor].agent_view[agent_index].1 != color{
+                                has_change = true;
+                            }
+                            graph[neighbor].agent_view[agent_index] = (neighbor_name, color);
+                            addition = false;
 
-    //need a new color
-    for node_color in &node.remaining{
-        //check if color is constrained
-        let mut is_constrained = false;
-        let mut all_satisfy = true;
-        for constrained_color in &node.no_good{
-            if *node_color == *constrained_color{
-                is_constrained = true;
-                break;
+                            break;
+                        }
+                    }
+                    if addition{//agent view of neighbor does not contain current node, push it
+                        graph[neighbor].agent_view.push((node_index, color));
+                        has_change = true;
+                    }
+                    graph[neighbor].modified = true;
+                }
             }
-        }
-        if is_constrained{
-            continue;
-        }
-        for (neighbor_name, neighbor_color) in &node.agent_view{
-            if *node_color == *neighbor_color{
-                all_satisfy = false;
-                break;
+        },
+
+        None => {
+            if graph[node_index].agent_view.is_empty(){//no solution and no where to backtrack
+                return None;
+            }else{//backtrack
+                let mut largest_node_index = 0;
+                let mut largest_color = Color(0);
+                let mut largest_vec_index = 0;
+                for (agent_index, (neighbor_name, neighbor_color)) in graph[node_index].agent_view.iter().enumerate(){
+                    if *neighbor_name >= largest_node_index{
+                        largest_node_index = *neighbor_name;
+                        largest_color = *neighbor_color;
+                        largest_vec_index = agent_index;
+                    }
+                }
+                //send nogood: the whole agent view, as the colors in it are
+                //what ruled out every color of ours
+                let context: Vec<NodeState> = graph[node_index].agent_view.iter().copied()
+                    .filter(|(neighbor_name, _)| *neighbor_name != largest_node_index).collect();
+                //the receiver has to hear from the nodes in the nogood it does not know yet
+                for (agent, agent_color) in context.iter().copied(){
+                    let receiver = &mut graph[largest_node_index];
+                    if receiver.agent_view.iter().all(|(known, _)| *known != agent){
+                        receiver.agent_view.push((agent, agent_color));
+                    }
+                    if !graph[agent].neighbors.contains(&largest_node_index) && !graph[agent].links.contains(&largest_node_index){
+                        graph[agent].links.push(largest_node_index);
+                    }
+                    graph[agent].modified = true;
+                }
+                graph[largest_node_index].no_good.push((largest_color, context));
+                graph[largest_node_index].modified = true;
+                graph[node_index].modified = true;
+                graph[node_index].agent_view.remove(largest_vec_index);
             }
         }
-        if all_satisfy{
-            return Some(*node_color);
-        }
     }
-    None
+    Some(has_change)
 }
 
 //single-threaded version of ABT
-fn abt_sequential(graph: &mut Vec<Node>) -> bool{
-    //let mut iter_index = 0;
-    //let mut no_solution = false;
+//max_rounds bounds the number of sweeps over the nodes (None runs until done)
+fn abt_sequential(graph: &mut [Node], num_colors: usize, max_rounds: Option<usize>) -> Outcome{
+    reset_graph(graph, num_colors);
     let mut has_change = true;
-    while (has_change){
+    let mut rounds = 0;
+    while has_change{
+        if max_rounds.is_some_and(|max| rounds >= max){
+            return Outcome::GaveUp;
+        }
+        rounds += 1;
         has_change = false;
         for node_index in 0..graph.len(){
+            match abt_step(graph, node_index){
+                None => return Outcome::NoSolution,
+                Some(changed) => has_change = changed || has_change,
+            }
+        }
 
-            //first determine a value
+    }
+    match all_consistent(graph){
+        true => Outcome::Colored,
+        false => Outcome::GaveUp,
+    }
+}
 
-            let (last_color, next_color) = get_last_and_next_color(graph, node_index);
-            if next_color != None{
-                graph[node_index].assign_color(next_color.unwrap());
-            }
-            if next_color != None && last_color != None{
-                if last_color.unwrap() != next_color.unwrap(){
-                    has_change = true;
+//multi-threaded version of ABT: every thread owns a contiguous range of nodes
+//and all threads step through their nodes in lock-step rounds
+//max_rounds bounds the number of rounds as in abt_sequential
+fn abt_parallel(graph: &mut Vec<Node>, num_colors: usize, num_threads: usize,
+                max_rounds: Option<usize>) -> Outcome{
+    reset_graph(graph, num_colors);
+    let num_agents = num_threads.max(1);
+    //first, partition the graph
+    let num_nodes = graph.len();
+    let agent_per_thread_upper: usize = num_nodes.div_ceil(num_agents);
+    let agent_per_thread_lower: usize = num_nodes / num_agents;
+    let lower_start: usize =
+        match agent_per_thread_upper == agent_per_thread_lower{
+            true => num_agents,
+            false => num_nodes % num_agents,
+        };
+
+    let shared = Arc::new(Mutex::new(mem::take(graph)));
+    let barrier = Arc::new(Barrier::new(num_agents));
+    let mut handles = Vec::new();
+    let stop = Arc::new(Mutex::new(false));
+    //idle[i] is true if thread i made no change in the current round
+    let idle_threads = Arc::new(Mutex::new(vec![false; num_agents]));
+    let gave_up = Arc::new(Mutex::new(false));
+
+    for i in 0..num_agents{
+        let graph_copy = Arc::clone(&shared);
+        let barrier_copy = barrier.clone();
+        let stop_copy = stop.clone();
+        let idle_threads_copy = idle_threads.clone();
+        let gave_up_copy = gave_up.clone();
+        let handle = thread::spawn(move||{
+            let start_index =
+                match i < lower_start{
+                    true => i * agent_per_thread_upper,
+                    false => lower_start * agent_per_thread_upper + (i-lower_start)*agent_per_thread_lower,
+            };
+            let end_index = {
+                match i < lower_start{
+                    true => start_index + agent_per_thread_upper,
+                    false => start_index + agent_per_thread_lower,
                 }
-            }else{
-                has_change = true;
-            }
-            graph[node_index].modified = true;
-            //println!("index:{}, color: {:?}", node_index, next_color);
-            match next_color{
-                //find a value
-                Some(color) => {
-                    //color is updated, update agent view
-                    if last_color == None || last_color.unwrap()==color{
-                        for neighbor in graph[node_index].neighbors.clone(){
-                            if graph[neighbor].name > node_index{
-                                let mut addition = true;
-                                for agent_index in 0..graph[neighbor].agent_view.len(){
-                                    let (neighbor_name, neighbor_color) = graph[neighbor].agent_view[agent_index];
-                                    if neighbor_name == node_index{//agent view of neighbor already contains current node, just need to update value
-                                        graph[neighbor].agent_view[agent_index] = (neighbor_name, next_color.unwrap());
-                                        addition = false;
-
-                                        break;
-                                    }
-                                }
-                                if addition{//agent view of neighbor does not contain current node, push it
-                                    graph[neighbor].agent_view.push((node_index, next_color.unwrap()));
-                                }
-                                graph[neighbor].modified = true;
-                            }
+            };
 
-                        }
-                    }
-                },
-
-                None => {
-                    if graph[node_index].agent_view.len() < 1{//no solution and no where to backtrack
-                        return false;
-                    }else{//backtrack
-                        let mut largest_node_index = 0;
-                        let mut largest_color:Color = Color::Red;
-                        let mut largest_vec_index = 0;
-                        for agent_index in 0..graph[node_index].agent_view.len(){
-                            let (neighbor_name, neighbor_color) = graph[node_index].agent_view[agent_index];
-                            if neighbor_name >= largest_node_index{
-                                largest_node_index = neighbor_name;
-                                largest_color = neighbor_color;
-                                largest_vec_index = agent_index;
-                            }
-                        }
-                        //send nogood
-                        graph[largest_node_index].no_good.push(largest_color);
-                        graph[largest_node_index].modified = true;
-                        graph[node_index].modified = true;
-                        graph[node_index].agent_view.remove(largest_vec_index);
+            let mut rounds = 0;
+            loop{
+                //every thread counts the same rounds, so they all give up together
+                if max_rounds.is_some_and(|max| rounds >= max){
+                    *gave_up_copy.lock().unwrap() = true;
+                    break;
+                }
+                rounds += 1;
+                let mut has_change = false;
+                for node_index in start_index..end_index{
+                    match abt_step(&mut graph_copy.lock().unwrap(), node_index){
+                        None => {
+                            *stop_copy.lock().unwrap() = true;
+                            break;
+                        },
+                        Some(changed) => has_change = changed || has_change,
                     }
                 }
+                idle_threads_copy.lock().unwrap()[i] = !has_change;
+                barrier_copy.wait();
+                let all_idle = idle_threads_copy.lock().unwrap().iter().all(|idle| *idle);
+                let stopped = *stop_copy.lock().unwrap();
+                //nobody may overwrite its idle flag before everyone has read them
+                barrier_copy.wait();
+                if all_idle || stopped{
+                    break;
+                }
             }
-
-        }
-
+        });
+        handles.push(handle);
     }
-    true
-}
 
-fn print_graph(graph: &Vec<Node>){
-    for node_index in 0..graph.len(){
-        println!("{:?}", graph[node_index].color);
+    for handle in handles{
+        handle.join().unwrap();
+    }
+    *graph = match Arc::try_unwrap(shared){
+        Ok(mutex) => mutex.into_inner().unwrap(),
+        Err(_) => unreachable!(),
+    };
+    if *stop.lock().unwrap(){
+        Outcome::NoSolution
+    }else if *gave_up.lock().unwrap() || !all_consistent(graph){
+        //threads that stopped between rounds can leave a conflict behind
+        Outcome::GaveUp
+    }else{
+        Outcome::Colored
     }
 }
 
-fn abt_alg(node: &mut Node){
-    if node.name == 0{
-        node.assign_color(Color::Red);
-    }else if node.name == 1{
-        node.assign_color(Color::Blue);
-    }else{
-        node.assign_color(Color::Green);
+fn print_graph(graph: &[Node]){
+    for node in graph{
+        match node.color{
+            Some(color) => println!("{} {}", node.name + 1, color.name()),
+            None => println!("{} uncolored", node.name + 1),
+        }
     }
 }
 
-fn get_last_and_next_color(graph: &mut Vec<Node>, node_index: usize) -> (Option<Color>, Option<Color>){
+fn get_last_and_next_color(graph: &mut [Node], node_index: usize) -> (Option<Color>, Option<Color>){
     let last_color = graph[node_index].color;
             let next_color =
                 match last_color{
                     None =>update_color(&graph[node_index]),
                     _ => match graph[node_index].modified{
-                        false => graph[node_index].color.clone(),
+                        false => graph[node_index].color,
                         _=> update_color(&graph[node_index]),
                     },
                 };
     (last_color, next_color)
 }
-fn main() {
+//check a coloring produced elsewhere against the graph
+fn verify(graph: &mut [Node], filename: &str) -> Result<(), String>{
+    solution::read_solution(filename, graph).map_err(|err| format!("{}: {}", filename, err))?;
+    let report = check_coloring(graph);
+    for (source, sink) in &report.conflicts{
+        println!("conflict: {} {} share color {}", source + 1, sink + 1,
+                 graph[*source].color.map_or(0, |color| color.index() + 1));
+    }
+    for vertex in &report.uncolored{
+        println!("uncolored: {}", vertex + 1);
+    }
+    if report.is_valid(){
+        println!("valid coloring with {} colors", report.num_colors);
+        Ok(())
+    }else{
+        Err(format!("invalid coloring: {} conflicting edges, {} uncolored vertices",
+                    report.conflicts.len(), report.uncolored.len()))
+    }
+}
 
+//run the configured algorithm as a yes/no question: can graph be colored
+//with num_colors colors
+fn decide(graph: &mut Vec<Node>, config: &Config, options: &SearchOptions, num_colors: usize) -> Outcome{
+    match config.algorithm{
+        Algorithm::Exhaustive => search::search(graph, num_colors, options).outcome,
+        Algorithm::ParallelExhaustive => parallel::parallel_search(graph, num_colors, options, config.num_threads).outcome,
+        Algorithm::AbtSequential => abt_sequential(graph, num_colors, config.max_rounds),
+        Algorithm::AbtParallel => abt_parallel(graph, num_colors, config.num_threads, config.max_rounds),
+        Algorithm::Dba => dba::dba(graph, num_colors, config.max_rounds, config.seed).outcome,
+        Algorithm::AbtDo => abt_do::abt_do(graph, num_colors, config.max_rounds, config.break_symmetry).outcome,
+        Algorithm::Dsatur | Algorithm::WelshPowell | Algorithm::SmallestLast =>
+            unreachable!("greedy colorers do not take a number of colors"),
+    }
+}
+
+//look for the chromatic number using the configured algorithm for each k
+fn run_chromatic(graph: &mut Vec<Node>, config: &Config, options: &SearchOptions) -> Result<(), String>{
+    if let Algorithm::Dsatur | Algorithm::WelshPowell | Algorithm::SmallestLast = config.algorithm{
+        return Err("--chromatic needs exhaustive, exhaustive-par, abt-seq, abt-par, abt-do or dba".to_string());
+    }
+    let now = Instant::now();
+    //dba only ever finds colorings; the others prove the dead ends they report
+    let complete = config.algorithm != Algorithm::Dba;
+    let result = chromatic::chromatic_number(graph, complete, |graph, num_colors| decide(graph, config, options, num_colors));
+    let duration = Instant::now().duration_since(now);
+
+    println!("greedy: {} colors in {:?}", result.greedy_colors, result.greedy_duration);
+    for attempt in &result.attempts{
+        let outcome = match attempt.outcome{
+            Outcome::Colored => "colored",
+            Outcome::NoSolution => "proven uncolorable",
+            Outcome::GaveUp => "gave up",
+        };
+        println!("k = {}: {} in {:?}", attempt.num_colors, outcome, attempt.duration);
+    }
+    if config.canonical{
+        symmetry::canonicalize(graph);
+    }
+    if !config.quiet{
+        print_graph(graph);
+    }
+    if result.is_exact(){
+        println!("chromatic number: {}", result.upper);
+    }else{
+        println!("chromatic number between {} and {}", result.lower, result.upper);
+    }
+    println!("time: {:?}", duration);
+    if let Some(filename) = &config.output{
+        solution::save_solution(graph, filename).map_err(|err| format!("{}: {}", filename, err))?;
+    }
+    Ok(())
+}
+
+fn run(config: &Config) -> Result<(), String>{
+    let mut graph = match &config.input{
+        Input::File(filename) => dimacs::read_graph(filename).map_err(|err| err.to_string())?,
+        Input::Generate(spec) => generators::from_spec(spec, config.seed)?,
+    };
+    if let Some(filename) = &config.convert_to{
+        return dimacs::save_graph(&graph, filename).map_err(|err| format!("{}: {}", filename, err));
+    }
+    if let Some(filename) = &config.verify{
+        return verify(&mut graph, filename);
+    }
+
+    let options = SearchOptions{
+        var_order: match &config.vertex_order{
+            Some(filename) => VarOrder::Given(search::read_order(filename, graph.len())?),
+            None => config.var_order.clone(),
+        },
+        value_order: config.value_order,
+        propagation: config.propagation,
+        backjumping: config.backjumping,
+        break_symmetry: config.break_symmetry,
+        seed: config.seed,
+    };
+    if config.break_symmetry && !matches!(config.algorithm, Algorithm::Exhaustive | Algorithm::ParallelExhaustive | Algorithm::AbtDo){
+        return Err("--break-symmetry needs exhaustive, exhaustive-par or abt-do".to_string());
+    }
+    if config.chromatic{
+        return run_chromatic(&mut graph, config, &options);
+    }
+
+    let now = Instant::now();
+    let outcome = match config.algorithm{
+        Algorithm::Dsatur | Algorithm::WelshPowell | Algorithm::SmallestLast => {
+            let used = match config.algorithm{
+                Algorithm::Dsatur => greedy::dsatur(&mut graph),
+                Algorithm::WelshPowell => greedy::welsh_powell(&mut graph),
+                _ => greedy::smallest_last(&mut graph),
+            };
+            println!("colors used: {}", used);
+            Outcome::Colored
+        },
+        Algorithm::Dba => {
+            let result = dba::dba(&mut graph, config.num_colors, config.max_rounds, config.seed);
+            println!("rounds: {}", result.rounds);
+            if result.outcome == Outcome::GaveUp{
+                println!("best coloring has {} conflicting edges", result.conflicts);
+            }
+            result.outcome
+        },
+        Algorithm::Exhaustive => {
+            let result = search::search(&mut graph, config.num_colors, &options);
+            println!("nodes: {}, backtracks: {}", result.nodes, result.backtracks);
+            if options.propagation != Propagation::None{
+                println!("prunings: {}, wipe-outs: {}", result.prunings, result.wipeouts);
+            }
+            if options.backjumping{
+                println!("backjumps: {}, levels skipped: {}, longest jump: {}",
+                         result.backjumps, result.levels_skipped, result.longest_jump);
+            }
+            result.outcome
+        },
+        Algorithm::ParallelExhaustive => {
+            let result = parallel::parallel_search(&mut graph, config.num_colors, &options, config.num_threads);
+            println!("nodes: {}, backtracks: {}, tasks: {}, steals: {}",
+                     result.nodes, result.backtracks, result.tasks, result.steals);
+            result.outcome
+        },
+        Algorithm::AbtDo => {
+            let result = abt_do::abt_do(&mut graph, config.num_colors, config.max_rounds, config.break_symmetry);
+            println!("rounds: {}, reorders: {}", result.rounds, result.reorders);
+            result.outcome
+        },
+        _ => decide(&mut graph, config, &options, config.num_colors),
+    };
+    let duration = Instant::now().duration_since(now);
+
+    if outcome == Outcome::Colored && config.canonical{
+        symmetry::canonicalize(&mut graph);
+    }
+    if outcome == Outcome::Colored && !config.quiet{
+        print_graph(&graph);
+    }
+    match outcome{
+        Outcome::Colored => println!("outcome: colored (consistent: {})", all_consistent(&graph)),
+        Outcome::NoSolution => println!("outcome: proven uncolorable with {} colors", config.num_colors),
+        Outcome::GaveUp => println!("outcome: gave up"),
+    }
+    println!("time: {:?}", duration);
+    if let (Outcome::Colored, Some(filename)) = (outcome, &config.output){
+        solution::save_solution(&graph, filename).map_err(|err| format!("{}: {}", filename, err))?;
+    }
+    Ok(())
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let config = match cli::parse_args(&args[1..]){
+        Ok(config) => config,
+        Err(message) => {
+            eprintln!("{}", message);
+            eprintln!("{}", cli::USAGE);
+            process::exit(2);
+        },
+    };
+    if let Err(message) = run(&config){
+        eprintln!("{}", message);
+        process::exit(1);
+    }
 }
 
 #[cfg(test)]
 mod tests{
     use super::*;
+
+    fn abt_alg(node: &mut Node){
+        if node.name == 0{
+            node.assign_color(Color(0));
+        }else if node.name == 1{
+            node.assign_color(Color(1));
+        }else{
+            node.assign_color(Color(2));
+        }
+    }
+
     fn gen_larger_graph()->Vec<Node>{
         let v0 = Node::new(0);
         let v1 = Node::new(1);
@@ -348,45 +673,6 @@ mod tests{
         graph
     }
 
-    fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-    where P: AsRef<Path>, {
-        let file = File::open(filename)?;
-        Ok(io::BufReader::new(file).lines())
-    }
-
-    fn read_graph(filename: &str) -> Vec<Node>{
-        //let file = File::open(filename).unwrap();
-        //let reader = BufReader::new(file);
-        let mut graph = Vec::new();
-        if let Ok(lines) = read_lines(filename) {
-                // Consumes the iterator, returns an (Optional) String
-                for line in lines {
-                    if let Ok(ip) = line {
-                        let slices = ip.split(" ").collect::<Vec<&str>>();
-                        if slices[0] == "p"{//the line contains information about number of nodes/edges
-                            let num_nodes: usize = slices[2].parse().unwrap();
-                            for i in 0..num_nodes{
-                                graph.push(Node::new(i));
-                            }
-                        }else if slices[0] == "e"{
-                            let source: usize = slices[1].parse().unwrap();
-                            let sink: usize = slices[2].parse().unwrap();
-                            graph[source-1].add_neighbor(sink-1);
-                            graph[sink-1].add_neighbor(source-1);
-                            //graph[sink].add_neighbor(source);
-                        }
-
-                    }
-                }
-            }
-        //let f2 = reader.get_ref();
-        graph
-    }
-
-    #[test]
-    fn file_test(){
-        read_graph("src/myciel3.sol");
-    }
     #[test]
     fn simple_c3(){
         let v1 = Node::new(0);
@@ -399,42 +685,130 @@ mod tests{
         graph[1].add_neighbor(0);
         graph[2].add_neighbor(0);
         graph[2].add_neighbor(1);
-        assert_eq!(exhaustive_search(&mut graph),true);
-        assert_eq!(all_consistent(&graph), true);
+        assert!(exhaustive_search(&mut graph, 3));
+        assert!(all_consistent(&graph));
+        assert!(!exhaustive_search(&mut graph, 2));
     }
 
     #[test]
     fn simple_invalid(){
         let mut graph = Vec::new();
-        let num_nodes = Color::num_colors()+1;
+        let num_colors = 3;
+        let num_nodes = num_colors+1;
         for i in 0..num_nodes{
             graph.push(Node::new(i));
         }
 
-        for node_index in 0..num_nodes{
+        for (node_index, node) in graph.iter_mut().enumerate(){
             for neighbor_index in 0..num_nodes{
                 if node_index != neighbor_index{
-                    graph[node_index].add_neighbor(neighbor_index);
+                    node.add_neighbor(neighbor_index);
                 }
             }
         }
+        assert!(!exhaustive_search(&mut graph, num_colors));
+        assert_eq!(abt_sequential(&mut graph, num_colors, None), Outcome::NoSolution);
+    }
 
+    #[test]
+    fn more_colors_than_named(){
+        //a complete graph on 9 nodes needs exactly 9 colors
+        let num_nodes = 9;
+        let mut graph = Vec::new();
+        for i in 0..num_nodes{
+            graph.push(Node::new(i));
+        }
+        for (node_index, node) in graph.iter_mut().enumerate(){
+            for neighbor_index in 0..num_nodes{
+                if node_index != neighbor_index{
+                    node.add_neighbor(neighbor_index);
+                }
+            }
+        }
+        assert!(exhaustive_search(&mut graph, 9));
+        assert!(all_consistent(&graph));
+        assert!(!exhaustive_search(&mut graph, 8));
+        assert_eq!(abt_sequential(&mut graph, 9, None), Outcome::Colored);
+        assert!(all_consistent(&graph));
+        assert_eq!(abt_parallel(&mut graph, 9, 4, None), Outcome::Colored);
+        assert!(all_consistent(&graph));
     }
 
     #[test]
     fn larger_graph_abt_seq(){
         let mut graph = gen_larger_graph();
         let now = Instant::now();
-        assert_eq!(abt_sequential(&mut graph), true);
+        assert_eq!(abt_sequential(&mut graph, 3, None), Outcome::Colored);
         let new_now = Instant::now();
         println!("abt seq duration: {:?}", new_now.duration_since(now));
     }
 
+    #[test]
+    fn nosol_graph_exhaustive(){
+        let mut graph = gen_nosol_graph();
+        //the triangle 0-1-4 rules out two colors
+        assert!(!exhaustive_search(&mut graph, 2));
+    }
+
+    #[test]
+    fn nosol_graph_propagation(){
+        let mut graph = gen_nosol_graph();
+        let plain = search::search(&mut graph, 2, &SearchOptions::default());
+        let options = SearchOptions{propagation: Propagation::ForwardChecking, ..SearchOptions::default()};
+        let fc = search::search(&mut graph, 2, &options);
+        assert_eq!(fc.outcome, Outcome::NoSolution);
+        assert!(fc.nodes < plain.nodes);
+        //arc consistency sees the triangle as soon as vertex 0 has a color
+        let options = SearchOptions{propagation: Propagation::ArcConsistency, ..SearchOptions::default()};
+        let ac = search::search(&mut graph, 2, &options);
+        assert_eq!(ac.outcome, Outcome::NoSolution);
+        assert_eq!(ac.nodes, 2);
+        assert_eq!(ac.wipeouts, 2);
+        assert_eq!(ac.backtracks, 1);
+    }
+
+    #[test]
+    fn abt_agrees_with_exhaustive(){
+        //nogoods that forget their context make ABT give up on colorable graphs
+        let mut graphs = Vec::new();
+        for seed in 1..=8{
+            graphs.push((generators::planted(30, 3, 0.3, &mut generators::Rng::new(seed)).0, 3));
+        }
+        for seed in 1..=10{
+            graphs.push((generators::gnp(14, 0.45, &mut generators::Rng::new(seed)), 4));
+        }
+        for (mut graph, num_colors) in graphs{
+            let expected = exhaustive_search(&mut graph, num_colors);
+            let outcome = abt_sequential(&mut graph, num_colors, None);
+            assert_eq!(outcome == Outcome::Colored, expected);
+            assert_eq!(outcome == Outcome::NoSolution, !expected);
+            assert_eq!(all_consistent(&graph), expected);
+            let outcome = abt_parallel(&mut graph, num_colors, 4, None);
+            assert_ne!(outcome == Outcome::NoSolution, expected);
+            assert_eq!(outcome == Outcome::Colored, all_consistent(&graph));
+        }
+    }
+
+    #[test]
+    fn abt_parallel_checks_its_coloring(){
+        let mut graph = generators::mycielski(3);
+        let outcome = abt_parallel(&mut graph, 4, 8, None);
+        assert_eq!(outcome == Outcome::Colored, all_consistent(&graph));
+        assert_ne!(outcome, Outcome::NoSolution);
+    }
+
+    #[test]
+    fn abt_round_limit(){
+        let mut graph = gen_larger_graph();
+        assert_eq!(abt_sequential(&mut graph, 3, Some(0)), Outcome::GaveUp);
+        assert_eq!(abt_parallel(&mut graph, 3, 2, Some(0)), Outcome::GaveUp);
+    }
+
     #[test]
     fn larger_graph_exhaustive(){
         let mut graph = gen_larger_graph();
         let now = Instant::now();
-        assert_eq!(exhaustive_search(&mut graph), true);
+        assert!(exhaustive_search(&mut graph, 3));
         let new_now = Instant::now();
         println!("abt exhaustive duration: {:?}", new_now.duration_since(now));
     }
@@ -462,7 +836,7 @@ mod tests{
         }
         //wait for all threads
         for handle in handles{
-            handle.join();
+            handle.join().unwrap();
         }
         //check if the colors are assigned correctly
         //for item in graph.lock().unwrap().iter(){
@@ -472,9 +846,11 @@ mod tests{
 
     #[test]
     fn ciel_exhaustive(){
-        let mut graph = read_graph("src/myciel5.sol");
+        //mycielski(5) has chromatic number 6
+        let mut graph = generators::mycielski(5);
         let now = Instant::now();
-        exhaustive_search(&mut graph);
+        assert!(exhaustive_search(&mut graph, 7));
+        assert!(all_consistent(&graph));
         let new_now = Instant::now();
 
         println!("ciel exhaustive: duration: {:?}", new_now.duration_since(now));
@@ -483,150 +859,33 @@ mod tests{
 
     #[test]
     fn ciel_sequential(){
-        let mut graph = read_graph("src/myciel7.sol");
+        //mycielski(7) needs 8 colors; a proof with 7 is out of reach, so the
+        //round limit makes ABT give up
+        let mut graph = generators::mycielski(7);
         let now = Instant::now();
-        abt_sequential(&mut graph);
+        assert_eq!(abt_sequential(&mut graph, 7, Some(50)), Outcome::GaveUp);
+        assert_eq!(abt_sequential(&mut graph, 8, None), Outcome::Colored);
+        assert!(all_consistent(&graph));
         let new_now = Instant::now();
 
         println!("ciel sequential: duration: {:?}", new_now.duration_since(now));
+
+        //mycielski(4) needs 5 colors, and ABT can prove that 3 are too few
+        let mut graph = generators::mycielski(4);
+        assert_eq!(abt_sequential(&mut graph, 3, None), Outcome::NoSolution);
     }
 
     #[test]
     fn ciel_parallel(){
-        let num_agents = 8;
-        let mut graph = read_graph("src/myciel7.sol");
-        //first, partition the graph
-        let num_nodes = graph.len();
-        let agent_per_thread_upper: usize = (num_nodes + num_agents - 1) / num_agents;
-        let agent_per_thread_lower: usize = num_nodes / num_agents;
-        let lower_start: usize =
-            match agent_per_thread_upper == agent_per_thread_lower{
-                true => num_agents,
-                false => num_nodes % num_agents,
-            };
-
-
-        let graph = Arc::new(Mutex::new(graph));
-        let barrier = Arc::new(Barrier::new(num_agents));
-        let mut handles = Vec::new();
-        let stop = Arc::new(Mutex::new(false));
-        let idle_threads = Arc::new(Mutex::new(0));
+        let mut graph = generators::mycielski(7);
         let now = Instant::now();
-
-        for i in 0..num_agents{
-            let graph_copy = Arc::clone(&graph);
-            let barrier_copy = barrier.clone();
-            let stop_copy = stop.clone();
-            let idle_threads_copy = idle_threads.clone();
-            let handle = thread::spawn(move||{
-                let start_index =
-                    match i < lower_start{
-                        true => i * agent_per_thread_upper,
-                        false => lower_start * agent_per_thread_upper + (i-lower_start)*agent_per_thread_lower,
-                };
-                let end_index = {
-                    match i < lower_start{
-                        true => start_index + agent_per_thread_upper,
-                        false => start_index + agent_per_thread_lower,
-                    }
-                };
-
-                loop{
-                    let mut has_change = false;
-                    for node_index in start_index..end_index{
-                        //println!("{}", node_index);
-                        let (last_color, next_color) = get_last_and_next_color(&mut graph_copy.lock().unwrap(), node_index);
-                        if next_color != None{
-                            graph_copy.lock().unwrap()[node_index].assign_color(next_color.unwrap());
-                        }
-                        if next_color != None && last_color != None{
-                            if last_color.unwrap() != next_color.unwrap(){
-                                has_change = true;
-                            }
-                        }else{
-                            has_change = true;
-                        }
-                        graph_copy.lock().unwrap()[node_index].modified = true;
-                        match next_color{
-                            //find a value
-                            Some(color) => {
-                                //color is updated, update agent view
-                                if last_color == None || last_color.unwrap()==color{
-                                    let mut graph = graph_copy.lock().unwrap();
-                                    for neighbor in graph[node_index].neighbors.clone(){
-                                        if graph[neighbor].name > node_index{
-                                            let mut addition = true;
-                                            for agent_index in 0..graph[neighbor].agent_view.len(){
-                                                let (neighbor_name, neighbor_color) = graph[neighbor].agent_view[agent_index];
-                                                if neighbor_name == node_index{//agent view of neighbor already contains current node, just need to update value
-                                                    graph[neighbor].agent_view[agent_index] = (neighbor_name, next_color.unwrap());
-                                                    addition = false;
-
-                                                    break;
-                                                }
-                                            }
-                                            if addition{//agent view of neighbor does not contain current node, push it
-                                                graph[neighbor].agent_view.push((node_index, next_color.unwrap()));
-                                            }
-                                            graph[neighbor].modified = true;
-                                        }
-
-                                    }
-                                }
-                            },
-
-                            None => {
-                                let mut graph = graph_copy.lock().unwrap();
-                                if graph[node_index].agent_view.len() < 1{//no solution and no where to backtrack
-                                    let mut stop = stop_copy.lock().unwrap();
-                                    *stop = true;
-                                    break;
-                                }else{//backtrack
-                                    let mut largest_node_index = 0;
-                                    let mut largest_color:Color = Color::Red;
-                                    let mut largest_vec_index = 0;
-                                    for agent_index in 0..graph[node_index].agent_view.len(){
-                                        let (neighbor_name, neighbor_color) = graph[node_index].agent_view[agent_index];
-                                        if neighbor_name >= largest_node_index{
-                                            largest_node_index = neighbor_name;
-                                            largest_color = neighbor_color;
-                                            largest_vec_index = agent_index;
-                                        }
-                                    }
-                                    //send nogood
-                                    graph[largest_node_index].no_good.push(largest_color);
-                                    graph[largest_node_index].modified = true;
-                                    graph[node_index].modified = true;
-                                    graph[node_index].agent_view.remove(largest_vec_index);
-                                }
-                            }
-                        }
-
-                    }
-                    if !has_change{
-                        let mut idle_threads = idle_threads_copy.lock().unwrap();
-                        *idle_threads = *idle_threads + 1;
-                    }
-                    barrier_copy.wait();
-                    if *(idle_threads_copy.lock().unwrap()) >= 8{
-                        break;
-                    }else{
-                        let mut idle_threads = idle_threads_copy.lock().unwrap();
-                        *idle_threads = 0;
-                    }
-                    if *(stop_copy.lock().unwrap()){
-                        break;
-                    }
-                }
-            });
-            handles.push(handle);
-        }
-
-        for handle in handles{
-            handle.join();
-        }
+        assert_eq!(abt_parallel(&mut graph, 7, 8, Some(50)), Outcome::GaveUp);
+        assert_eq!(abt_parallel(&mut graph, 8, 8, None), Outcome::Colored);
+        assert!(all_consistent(&graph));
         let new_now = Instant::now();
         println!("ciel par: {:?}", new_now.duration_since(now));
 
+        let mut graph = generators::mycielski(4);
+        assert_eq!(abt_parallel(&mut graph, 3, 8, None), Outcome::NoSolution);
     }
 }
@@ -0,0 +1,198 @@
+//exhaustive search on several threads, as a shared-memory baseline for ABT.
+//The tree is cut into tasks, each a prefix of assignments whose subtree one
+//thread searches. Every thread keeps its tasks in a deque, takes work from
+//the back and steals from the front of the others when it runs dry. While
+//some thread is idle, a thread whose deque is empty gives the untried colors
+//of the shallowest open level of its search to its deque, so the tasks that
+//get stolen are the big ones near the root. The first coloring found stops
+//every thread.
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use crate::search::{self, Search, SearchOptions};
+use crate::{reset_graph, Color, Node, Outcome};
+
+pub struct ParallelResult{
+    pub outcome: Outcome,//Colored or NoSolution
+    pub nodes: usize,//colors assigned by all threads together
+    pub backtracks: usize,
+    pub tasks: usize,//subtrees searched, the whole tree included
+    pub steals: usize,//tasks taken from another thread's deque
+}
+
+type Task = Vec<(usize, usize)>;
+
+struct Pool{
+    deques: Vec<Mutex<VecDeque<Task>>>,
+    pending: AtomicUsize,//tasks queued or running
+    idle: AtomicUsize,//threads looking for a task
+    found: AtomicBool,
+    solution: Mutex<Vec<Option<usize>>>,
+    tasks: AtomicUsize,
+    steals: AtomicUsize,
+    nodes: AtomicUsize,
+    backtracks: AtomicUsize,
+}
+
+impl Pool{
+    fn push(&self, me: usize, task: Task){
+        self.pending.fetch_add(1, Ordering::SeqCst);
+        self.tasks.fetch_add(1, Ordering::Relaxed);
+        self.deques[me].lock().unwrap().push_back(task);
+    }
+
+    fn take(&self, me: usize) -> Option<Task>{
+        if let Some(task) = self.deques[me].lock().unwrap().pop_back(){
+            return Some(task);
+        }
+        let num_threads = self.deques.len();
+        for offset in 1..num_threads{
+            let victim = (me + offset) % num_threads;
+            if let Some(task) = self.deques[victim].lock().unwrap().pop_front(){
+                self.steals.fetch_add(1, Ordering::Relaxed);
+                return Some(task);
+            }
+        }
+        None
+    }
+}
+
+fn worker(pool: &Pool, me: usize, graph: &[Node], num_colors: usize, options: &SearchOptions,
+          order: Option<&[usize]>){
+    let mut waiting = false;
+    while !pool.found.load(Ordering::SeqCst){
+        let task = match pool.take(me){
+            Some(task) => task,
+            None => {
+                //every task is finished once none is queued or running
+                if pool.pending.load(Ordering::SeqCst) == 0{
+                    break;
+                }
+                if !waiting{
+                    waiting = true;
+                    pool.idle.fetch_add(1, Ordering::SeqCst);
+                }
+                thread::yield_now();
+                continue;
+            },
+        };
+        if waiting{
+            waiting = false;
+            pool.idle.fetch_sub(1, Ordering::SeqCst);
+        }
+
+        let mut search = Search::new(graph, num_colors, options, order);
+        let colored = search.start_from(&task) && search.run(|search| {
+            if pool.found.load(Ordering::Relaxed){
+                return false;
+            }
+            let starving = pool.idle.load(Ordering::Relaxed) > 0 && pool.deques[me].lock().unwrap().is_empty();
+            if starving{
+                for task in search.split(){
+                    pool.push(me, task);
+                }
+            }
+            true
+        }) == Some(true);
+        let result = search.result(Outcome::NoSolution);
+        pool.nodes.fetch_add(result.nodes, Ordering::Relaxed);
+        pool.backtracks.fetch_add(result.backtracks, Ordering::Relaxed);
+        if colored{
+            *pool.solution.lock().unwrap() = search.colors().to_vec();
+            pool.found.store(true, Ordering::SeqCst);
+        }
+        pool.pending.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+//looks for a coloring with num_colors colors on num_threads threads; like
+//search::search, a coloring found is left in the graph, otherwise the graph
+//is left uncolored
+pub fn parallel_search(graph: &mut [Node], num_colors: usize, options: &SearchOptions,
+                       num_threads: usize) -> ParallelResult{
+    reset_graph(graph, num_colors);
+    let order = search::static_order(graph, &options.var_order);
+    let pool = Pool{
+        deques: (0..num_threads).map(|_| Mutex::new(VecDeque::new())).collect(),
+        pending: AtomicUsize::new(0),
+        idle: AtomicUsize::new(0),
+        found: AtomicBool::new(false),
+        solution: Mutex::new(Vec::new()),
+        tasks: AtomicUsize::new(0),
+        steals: AtomicUsize::new(0),
+        nodes: AtomicUsize::new(0),
+        backtracks: AtomicUsize::new(0),
+    };
+    pool.push(0, Vec::new());
+    let shared: &[Node] = graph;
+    thread::scope(|scope| {
+        for me in 0..num_threads{
+            let (pool, order) = (&pool, order.as_deref());
+            scope.spawn(move || worker(pool, me, shared, num_colors, options, order));
+        }
+    });
+
+    let found = pool.found.load(Ordering::SeqCst);
+    if found{
+        let colors = pool.solution.into_inner().unwrap();
+        for (node, color) in graph.iter_mut().zip(colors){
+            node.color = color.map(Color);
+        }
+    }
+    ParallelResult{
+        outcome: if found {Outcome::Colored} else {Outcome::NoSolution},
+        nodes: pool.nodes.into_inner(),
+        backtracks: pool.backtracks.into_inner(),
+        tasks: pool.tasks.into_inner(),
+        steals: pool.steals.into_inner(),
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use crate::generators::{self, Rng};
+    use crate::search::{Propagation, VarOrder};
+    use crate::{all_consistent, exhaustive_search};
+
+    #[test]
+    fn agrees_with_sequential_search(){
+        let mut rng = Rng::new(11);
+        let mut graphs = vec![generators::mycielski(3), generators::queen_graph(5, 5)];
+        for _ in 0..10{
+            graphs.push(generators::gnp(18, 0.4, &mut rng));
+        }
+        let options = vec![
+            SearchOptions::default(),
+            SearchOptions{var_order: VarOrder::Dsatur, propagation: Propagation::ForwardChecking,
+                          backjumping: true, break_symmetry: true, ..SearchOptions::default()},
+        ];
+        for mut graph in graphs{
+            for num_colors in 3..6{
+                let expected = exhaustive_search(&mut graph, num_colors);
+                for options in &options{
+                    for num_threads in [1, 4]{
+                        let result = parallel_search(&mut graph, num_colors, options, num_threads);
+                        assert_eq!(result.outcome == Outcome::Colored, expected);
+                        assert_eq!(all_consistent(&graph), expected);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn work_is_shared(){
+        //a proof has to visit the whole tree, so idle threads ask for work
+        let mut graph = generators::queen_graph(6, 6);
+        let result = parallel_search(&mut graph, 6, &SearchOptions::default(), 4);
+        assert_eq!(result.outcome, Outcome::NoSolution);
+        assert!(result.tasks > 1);
+        assert!(graph.iter().all(|node| node.color.is_none()));
+
+        let result = parallel_search(&mut graph, 6, &SearchOptions::default(), 1);
+        assert_eq!(result.tasks, 1);
+        assert_eq!(result.steals, 0);
+    }
+}
@@ -0,0 +1,687 @@
+//depth-first search for a k-coloring with pluggable variable and value
+//orderings, optional propagation, conflict-directed backjumping and symmetry
+//breaking. The default (vertices by index, largest color first, no
+//propagation, one level back at a time, every color everywhere) walks the
+//same tree as the original exhaustive_search; the rest make it a fair
+//baseline for the distributed algorithms.
+use std::collections::BTreeSet;
+use std::fs;
+use crate::generators::Rng;
+use crate::greedy;
+use crate::{reset_graph, Color, Node, Outcome};
+
+//which vertex to color next
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum VarOrder{
+    Index,
+    MaxDegree,
+    //the uncolored vertex with the most distinct neighbor colors, ties to the
+    //larger degree; chosen again at every depth
+    Dsatur,
+    //smallest-last order, so every vertex has few neighbors after it
+    Degeneracy,
+    Given(Vec<usize>),
+}
+
+//in which order to try the colors of a vertex
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ValueOrder{
+    LastFirst,//largest color first, as the original search popped remaining
+    LeastConstraining,//the color that the fewest uncolored neighbors could still take first
+    Random,
+}
+
+//what to do with the domains of the uncolored vertices after each assignment
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Propagation{
+    None,//only check new colors against the colored neighbors
+    ForwardChecking,//take the color out of the neighbors' domains, fail on a wipe-out
+    //forward checking, then AC-3: a vertex left with one color takes it out of
+    //its neighbors' domains, and so on
+    ArcConsistency,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct SearchOptions{
+    pub var_order: VarOrder,
+    pub value_order: ValueOrder,
+    pub propagation: Propagation,
+    //when a vertex runs out of colors, go back to the deepest vertex that
+    //took part in ruling them out instead of the one colored just before
+    pub backjumping: bool,
+    //value precedence: a vertex may only open the next unused color, so of
+    //the k! colorings that differ by a renaming of the colors only one is
+    //searched
+    pub break_symmetry: bool,
+    pub seed: u64,//for ValueOrder::Random
+}
+
+impl Default for SearchOptions{
+    fn default() -> SearchOptions{
+        SearchOptions{
+            var_order: VarOrder::Index,
+            value_order: ValueOrder::LastFirst,
+            propagation: Propagation::None,
+            backjumping: false,
+            break_symmetry: false,
+            seed: 1,
+        }
+    }
+}
+
+pub struct SearchResult{
+    pub outcome: Outcome,//Colored or NoSolution
+    pub nodes: usize,//colors assigned during the search
+    pub backtracks: usize,
+    pub prunings: usize,//colors taken out of uncolored domains by propagation
+    pub wipeouts: usize,//assignments undone because a domain became empty
+    pub backjumps: usize,//backtracks that went back more than one level
+    pub levels_skipped: usize,//levels jumped over by all backjumps together
+    pub longest_jump: usize,//the most levels one backtrack went back
+}
+
+//one level of the search: the vertex, its colors in the order to try them,
+//and how far we got
+struct Frame{
+    vertex: usize,
+    candidates: Vec<usize>,
+    next: usize,
+    trail: usize,//length of the pruning trail before this level's color
+    //for backjumping: the shallower levels whose colors ruled out the colors
+    //tried so far
+    conflicts: BTreeSet<usize>,
+}
+
+struct State<'a>{
+    graph: &'a [Node],
+    num_colors: usize,
+    colors: Vec<Option<usize>>,
+    depth: Vec<usize>,//the level each colored vertex was colored at
+    //taken[v * num_colors + c] counts the neighbors of v colored c
+    taken: Vec<usize>,
+    //the shallowest of those; the colors come and go in stack order, so it is
+    //the level that made the count nonzero
+    taken_at: Vec<usize>,
+    saturation: Vec<usize>,
+    //colors taken out of a domain by arc consistency, undone from the trail,
+    //and the level whose propagation took them out
+    pruned: Vec<bool>,
+    pruned_at: Vec<usize>,
+    trail: Vec<usize>,
+    //colors left to a vertex: neither taken nor pruned
+    domain_size: Vec<usize>,
+    //vertices per color; with value precedence the colors in use are always
+    //0..num_used
+    uses: Vec<usize>,
+    num_used: usize,
+    precedence: bool,
+    counting: bool,//whether domain shrinking counts as pruning
+    prunings: usize,
+}
+
+impl<'a> State<'a>{
+    fn available(&self, vertex: usize, color: usize) -> bool{
+        let slot = vertex * self.num_colors + color;
+        self.taken[slot] == 0 && !self.pruned[slot]
+    }
+
+    fn assign(&mut self, vertex: usize, color: usize, depth: usize){
+        self.colors[vertex] = Some(color);
+        self.depth[vertex] = depth;
+        if self.uses[color] == 0{
+            self.num_used += 1;
+        }
+        self.uses[color] += 1;
+        for neighbor in &self.graph[vertex].neighbors{
+            let slot = neighbor * self.num_colors + color;
+            if self.taken[slot] == 0{
+                self.taken_at[slot] = depth;
+                self.saturation[*neighbor] += 1;
+                if !self.pruned[slot]{
+                    self.domain_size[*neighbor] -= 1;
+                    if self.counting && self.colors[*neighbor].is_none(){
+                        self.prunings += 1;
+                    }
+                }
+            }
+            self.taken[slot] += 1;
+        }
+    }
+
+    fn unassign(&mut self, vertex: usize){
+        let color = self.colors[vertex].take().unwrap();
+        self.uses[color] -= 1;
+        if self.uses[color] == 0{
+            self.num_used -= 1;
+        }
+        for neighbor in &self.graph[vertex].neighbors{
+            let slot = neighbor * self.num_colors + color;
+            self.taken[slot] -= 1;
+            if self.taken[slot] == 0{
+                self.saturation[*neighbor] -= 1;
+                if !self.pruned[slot]{
+                    self.domain_size[*neighbor] += 1;
+                }
+            }
+        }
+    }
+
+    fn prune(&mut self, vertex: usize, color: usize, depth: usize){
+        let slot = vertex * self.num_colors + color;
+        self.pruned[slot] = true;
+        self.pruned_at[slot] = depth;
+        self.trail.push(slot);
+        self.domain_size[vertex] -= 1;
+        self.prunings += 1;
+    }
+
+    fn undo(&mut self, trail: usize){
+        while self.trail.len() > trail{
+            let slot = self.trail.pop().unwrap();
+            self.pruned[slot] = false;
+            if self.taken[slot] == 0{
+                self.domain_size[slot / self.num_colors] += 1;
+            }
+        }
+    }
+
+    //adds to conflicts the levels that keep color away from vertex. Arc
+    //consistency does not record why it pruned a color, so a pruned color
+    //blames every level down to the one that pruned it
+    fn explain(&self, vertex: usize, color: usize, conflicts: &mut BTreeSet<usize>){
+        let slot = vertex * self.num_colors + color;
+        if self.taken[slot] > 0{
+            conflicts.insert(self.taken_at[slot]);
+        }else if self.pruned[slot]{
+            conflicts.extend(0..=self.pruned_at[slot]);
+        }
+    }
+
+    //after vertex got its color: Err with a vertex that has no color left, if
+    //there is one
+    fn propagate(&mut self, vertex: usize, propagation: Propagation) -> Result<(), usize>{
+        let graph = self.graph;
+        let depth = self.depth[vertex];
+        let mut singletons = Vec::new();
+        for neighbor in &graph[vertex].neighbors{
+            if self.colors[*neighbor].is_some(){
+                continue;
+            }
+            match self.domain_size[*neighbor]{
+                0 => return Err(*neighbor),
+                1 => singletons.push(*neighbor),
+                _ => (),
+            }
+        }
+        if propagation != Propagation::ArcConsistency{
+            return Ok(());
+        }
+        //for u != w the arc (w, u) only removes a color from w when u has no
+        //other color left
+        while let Some(single) = singletons.pop(){
+            if self.domain_size[single] != 1{
+                //already failed, or it got colored meanwhile
+                continue;
+            }
+            let color = (0..self.num_colors).find(|color| self.available(single, *color)).unwrap();
+            for neighbor in &graph[single].neighbors{
+                if self.colors[*neighbor].is_some() || !self.available(*neighbor, color){
+                    continue;
+                }
+                self.prune(*neighbor, color, depth);
+                match self.domain_size[*neighbor]{
+                    0 => return Err(*neighbor),
+                    1 => singletons.push(*neighbor),
+                    _ => (),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn pick_dsatur(&self) -> usize{
+        (0..self.graph.len())
+            .filter(|vertex| self.colors[*vertex].is_none())
+            .max_by_key(|vertex| (self.saturation[*vertex], self.graph[*vertex].neighbors.len(),
+                                  std::cmp::Reverse(*vertex)))
+            .unwrap()
+    }
+
+    fn candidates(&self, vertex: usize, value_order: ValueOrder, rng: &mut Rng) -> Vec<usize>{
+        let mut candidates: Vec<usize> = (0..self.num_colors)
+            .filter(|color| self.available(vertex, *color) && (!self.precedence || *color <= self.num_used))
+            .collect();
+        match value_order{
+            ValueOrder::LastFirst => candidates.reverse(),
+            ValueOrder::LeastConstraining => candidates.sort_by_cached_key(|color| {
+                self.graph[vertex].neighbors.iter()
+                    .filter(|neighbor| self.colors[**neighbor].is_none() && self.available(**neighbor, *color))
+                    .count()
+            }),
+            ValueOrder::Random => for i in (1..candidates.len()).rev(){
+                candidates.swap(i, rng.below(i + 1));
+            },
+        }
+        candidates
+    }
+}
+
+//how many colors a search assigns between two calls of its keep_going
+const POLL_NODES: usize = 256;
+
+//a depth-first search that can start below a fixed prefix of assignments,
+//stop when asked and give away its untried colors; search runs one over the
+//whole tree and the parallel solver one per task
+pub struct Search<'a>{
+    state: State<'a>,
+    options: &'a SearchOptions,
+    order: Option<&'a [usize]>,
+    rng: Rng,
+    frames: Vec<Frame>,
+    base: usize,//levels fixed by the prefix, never backtracked into
+    next_poll: usize,
+    nodes: usize,
+    backtracks: usize,
+    wipeouts: usize,
+    backjumps: usize,
+    levels_skipped: usize,
+    longest_jump: usize,
+}
+
+impl<'a> Search<'a>{
+    //order is the static vertex order, None for DSATUR
+    pub fn new(graph: &'a [Node], num_colors: usize, options: &'a SearchOptions,
+               order: Option<&'a [usize]>) -> Search<'a>{
+        let state = State{
+            graph,
+            num_colors,
+            colors: vec![None; graph.len()],
+            depth: vec![0; graph.len()],
+            taken: vec![0; graph.len() * num_colors],
+            taken_at: vec![0; graph.len() * num_colors],
+            saturation: vec![0; graph.len()],
+            pruned: vec![false; graph.len() * num_colors],
+            pruned_at: vec![0; graph.len() * num_colors],
+            trail: Vec::new(),
+            domain_size: vec![num_colors; graph.len()],
+            uses: vec![0; num_colors],
+            num_used: 0,
+            precedence: options.break_symmetry,
+            counting: options.propagation != Propagation::None,
+            prunings: 0,
+        };
+        Search{
+            state,
+            options,
+            order,
+            rng: Rng::new(options.seed),
+            frames: Vec::new(),
+            base: 0,
+            next_poll: POLL_NODES,
+            nodes: 0,
+            backtracks: 0,
+            wipeouts: 0,
+            backjumps: 0,
+            levels_skipped: 0,
+            longest_jump: 0,
+        }
+    }
+
+    //fixes the (vertex, color) pairs of prefix, in order, as the top levels;
+    //false if propagating them already empties a domain
+    pub fn start_from(&mut self, prefix: &[(usize, usize)]) -> bool{
+        for (vertex, color) in prefix{
+            let depth = self.frames.len();
+            let trail = self.state.trail.len();
+            self.frames.push(Frame{vertex: *vertex, candidates: vec![*color], next: 1, trail, conflicts: BTreeSet::new()});
+            self.state.assign(*vertex, *color, depth);
+            if self.options.propagation != Propagation::None && self.state.propagate(*vertex, self.options.propagation).is_err(){
+                return false;
+            }
+        }
+        self.base = prefix.len();
+        true
+    }
+
+    //searches below the prefix; Some(true) with a coloring, Some(false) when
+    //there is none, None if keep_going, called every POLL_NODES colors, said
+    //to stop
+    pub fn run<F: FnMut(&mut Search<'a>) -> bool>(&mut self, mut keep_going: F) -> Option<bool>{
+        let num_colors = self.state.num_colors;
+        let options = self.options;
+        loop{
+            let depth = self.frames.len();
+            if depth == self.state.graph.len(){
+                return Some(true);
+            }
+            if self.nodes >= self.next_poll{
+                self.next_poll = self.nodes + POLL_NODES;
+                if !keep_going(self){
+                    return None;
+                }
+            }
+            let vertex = match self.order{
+                Some(order) => order[depth],
+                None => self.state.pick_dsatur(),
+            };
+            let candidates = self.state.candidates(vertex, options.value_order, &mut self.rng);
+            let trail = self.state.trail.len();
+            self.frames.push(Frame{vertex, candidates, next: 0, trail, conflicts: BTreeSet::new()});
+
+            //move to the next color, backtracking over exhausted levels
+            let state = &mut self.state;
+            let frames = &mut self.frames;
+            loop{
+                let depth = match frames.len().checked_sub(1){
+                    Some(depth) => depth,
+                    None => return Some(false),
+                };
+                let frame = &mut frames[depth];
+                if state.colors[frame.vertex].is_some(){
+                    state.unassign(frame.vertex);
+                    state.undo(frame.trail);
+                }
+                if frame.next < frame.candidates.len(){
+                    let color = frame.candidates[frame.next];
+                    frame.next += 1;
+                    state.assign(frame.vertex, color, depth);
+                    self.nodes += 1;
+                    if options.propagation != Propagation::None{
+                        if let Err(empty) = state.propagate(frame.vertex, options.propagation){
+                            self.wipeouts += 1;
+                            if options.backjumping{
+                                for color in 0..num_colors{
+                                    state.explain(empty, color, &mut frame.conflicts);
+                                }
+                                frame.conflicts.remove(&depth);
+                            }
+                            continue;
+                        }
+                    }
+                    break;
+                }
+                self.backtracks += 1;
+                if !options.backjumping{
+                    frames.pop();
+                    if frames.len() <= self.base{
+                        return Some(false);
+                    }
+                    continue;
+                }
+                //the colors that were never candidates are kept away by the
+                //levels above, which is the same now as when the frame was
+                //pushed; a color that value precedence held back or that was
+                //given away depends on all of them
+                let mut frame = frames.pop().unwrap();
+                for color in 0..num_colors{
+                    if frame.candidates.contains(&color){
+                        continue;
+                    }
+                    if state.available(frame.vertex, color){
+                        frame.conflicts.extend(0..depth);
+                    }else{
+                        state.explain(frame.vertex, color, &mut frame.conflicts);
+                    }
+                }
+                let target = match frame.conflicts.iter().next_back(){
+                    //the prefix is to blame, or nothing above is and the
+                    //vertex cannot be colored at all
+                    Some(target) if *target >= self.base => *target,
+                    _ => return Some(false),
+                };
+                let jump = depth - target;
+                if jump > 1{
+                    self.backjumps += 1;
+                    self.levels_skipped += jump - 1;
+                }
+                self.longest_jump = self.longest_jump.max(jump);
+                while frames.len() > target + 1{
+                    let skipped = frames.pop().unwrap();
+                    state.unassign(skipped.vertex);
+                    state.undo(skipped.trail);
+                }
+                frame.conflicts.remove(&target);
+                frames[target].conflicts.append(&mut frame.conflicts);
+            }
+        }
+    }
+
+    //takes the untried colors of the shallowest level below the prefix out
+    //of this search and returns them as prefixes for other searches
+    pub fn split(&mut self) -> Vec<Vec<(usize, usize)>>{
+        let level = match (self.base..self.frames.len()).find(|level| {
+            let frame = &self.frames[*level];
+            frame.next < frame.candidates.len()
+        }){
+            Some(level) => level,
+            None => return Vec::new(),
+        };
+        let prefix: Vec<(usize, usize)> = self.frames[..level].iter()
+            .map(|frame| (frame.vertex, self.state.colors[frame.vertex].unwrap()))
+            .collect();
+        let frame = &mut self.frames[level];
+        let tasks = frame.candidates[frame.next..].iter().map(|color| {
+            let mut task = prefix.clone();
+            task.push((frame.vertex, *color));
+            task
+        }).collect();
+        frame.candidates.truncate(frame.next);
+        tasks
+    }
+
+    pub fn colors(&self) -> &[Option<usize>]{
+        &self.state.colors
+    }
+
+    pub fn result(&self, outcome: Outcome) -> SearchResult{
+        SearchResult{
+            outcome,
+            nodes: self.nodes,
+            backtracks: self.backtracks,
+            prunings: self.state.prunings,
+            wipeouts: self.wipeouts,
+            backjumps: self.backjumps,
+            levels_skipped: self.levels_skipped,
+            longest_jump: self.longest_jump,
+        }
+    }
+}
+
+//the static vertex order, or None for DSATUR
+pub fn static_order(graph: &[Node], var_order: &VarOrder) -> Option<Vec<usize>>{
+    let mut order: Vec<usize> = (0..graph.len()).collect();
+    match var_order{
+        VarOrder::Index => (),
+        VarOrder::MaxDegree => order.sort_by_key(|index| std::cmp::Reverse(graph[*index].neighbors.len())),
+        VarOrder::Dsatur => return None,
+        VarOrder::Degeneracy => order = greedy::smallest_last_order(graph),
+        VarOrder::Given(given) => order = given.clone(),
+    }
+    Some(order)
+}
+
+//looks for a coloring with num_colors colors; a coloring found is left in
+//the graph, otherwise the graph is left uncolored
+pub fn search(graph: &mut [Node], num_colors: usize, options: &SearchOptions) -> SearchResult{
+    reset_graph(graph, num_colors);
+    let order = static_order(graph, &options.var_order);
+    let mut search = Search::new(graph, num_colors, options, order.as_deref());
+    let colored = search.run(|_| true) == Some(true);
+    let result = search.result(if colored {Outcome::Colored} else {Outcome::NoSolution});
+    let colors = search.colors().to_vec();
+    for (node, color) in graph.iter_mut().zip(colors){
+        node.color = color.map(Color);
+    }
+    result
+}
+
+//a vertex order given as 1-based vertex numbers separated by whitespace; it
+//must name every vertex exactly once
+pub fn parse_order(text: &str, num_nodes: usize) -> Result<Vec<usize>, String>{
+    let mut seen = vec![false; num_nodes];
+    let mut order = Vec::with_capacity(num_nodes);
+    for word in text.split_whitespace(){
+        let vertex: usize = word.parse().map_err(|_| format!("bad vertex in order: {}", word))?;
+        if vertex == 0 || vertex > num_nodes{
+            return Err(format!("vertex {} out of range 1..{}", vertex, num_nodes));
+        }
+        if seen[vertex - 1]{
+            return Err(format!("vertex {} appears twice in the order", vertex));
+        }
+        seen[vertex - 1] = true;
+        order.push(vertex - 1);
+    }
+    if order.len() != num_nodes{
+        return Err(format!("order names {} of {} vertices", order.len(), num_nodes));
+    }
+    Ok(order)
+}
+
+pub fn read_order(filename: &str, num_nodes: usize) -> Result<Vec<usize>, String>{
+    let text = fs::read_to_string(filename).map_err(|err| format!("{}: {}", filename, err))?;
+    parse_order(&text, num_nodes).map_err(|err| format!("{}: {}", filename, err))
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use crate::{all_consistent, generators};
+
+    fn all_options() -> Vec<SearchOptions>{
+        let var_orders = vec![VarOrder::Index, VarOrder::MaxDegree, VarOrder::Dsatur, VarOrder::Degeneracy];
+        let value_orders = [ValueOrder::LastFirst, ValueOrder::LeastConstraining, ValueOrder::Random];
+        let propagations = [Propagation::None, Propagation::ForwardChecking, Propagation::ArcConsistency];
+        let mut options = Vec::new();
+        for var_order in &var_orders{
+            for value_order in &value_orders{
+                for propagation in &propagations{
+                    for (backjumping, break_symmetry) in [(false, false), (true, false), (false, true), (true, true)]{
+                        options.push(SearchOptions{
+                            var_order: var_order.clone(),
+                            value_order: *value_order,
+                            propagation: *propagation,
+                            backjumping,
+                            break_symmetry,
+                            seed: 7,
+                        });
+                    }
+                }
+            }
+        }
+        options
+    }
+
+    #[test]
+    fn every_ordering_decides_correctly(){
+        let cases = vec![
+            (generators::mycielski(3), 4),
+            (generators::queen_graph(5, 5), 5),
+            (generators::queen_graph(1, 6), 6),
+        ];
+        for (mut graph, chromatic) in cases{
+            for options in all_options(){
+                let result = search(&mut graph, chromatic, &options);
+                assert_eq!(result.outcome, Outcome::Colored, "{:?}", options);
+                assert!(all_consistent(&graph));
+                let result = search(&mut graph, chromatic - 1, &options);
+                assert_eq!(result.outcome, Outcome::NoSolution, "{:?}", options);
+                assert!(graph.iter().all(|node| node.color.is_none()));
+            }
+        }
+    }
+
+    #[test]
+    fn heuristics_shrink_the_tree(){
+        let mut graph = generators::queen_graph(6, 6);
+        let plain = search(&mut graph, 7, &SearchOptions::default());
+        let options = SearchOptions{var_order: VarOrder::Dsatur, ..SearchOptions::default()};
+        let dsatur = search(&mut graph, 7, &options);
+        assert!(dsatur.nodes < plain.nodes);
+        assert!(dsatur.backtracks <= plain.backtracks);
+    }
+
+    #[test]
+    fn propagation_prunes(){
+        let mut graph = generators::queen_graph(5, 5);
+        let plain = search(&mut graph, 4, &SearchOptions::default());
+        assert_eq!(plain.prunings, 0);
+        assert_eq!(plain.wipeouts, 0);
+        let options = SearchOptions{propagation: Propagation::ForwardChecking, ..SearchOptions::default()};
+        let fc = search(&mut graph, 4, &options);
+        assert_eq!(fc.outcome, Outcome::NoSolution);
+        assert!(fc.nodes <= plain.nodes && fc.backtracks < plain.backtracks);
+        assert!(fc.prunings > 0 && fc.wipeouts > 0);
+        let options = SearchOptions{propagation: Propagation::ArcConsistency, ..SearchOptions::default()};
+        let ac = search(&mut graph, 4, &options);
+        assert_eq!(ac.outcome, Outcome::NoSolution);
+        assert!(ac.nodes < fc.nodes);
+        assert!(ac.wipeouts > 0);
+    }
+
+    #[test]
+    fn backjumping_skips_levels(){
+        let mut graph = generators::queen_graph(6, 6);
+        let plain = search(&mut graph, 7, &SearchOptions::default());
+        assert_eq!(plain.backjumps, 0);
+        let options = SearchOptions{backjumping: true, ..SearchOptions::default()};
+        let jumping = search(&mut graph, 7, &options);
+        assert_eq!(jumping.outcome, Outcome::Colored);
+        assert!(all_consistent(&graph));
+        assert!(jumping.nodes < plain.nodes);
+        assert!(jumping.backjumps > 0 && jumping.longest_jump > 1);
+        assert!(jumping.levels_skipped >= jumping.backjumps);
+    }
+
+    #[test]
+    fn backjumping_agrees_with_backtracking(){
+        let mut rng = Rng::new(5);
+        for _ in 0..20{
+            let mut graph = generators::gnp(16, 0.4, &mut rng);
+            for num_colors in 2..6{
+                let expected = search(&mut graph, num_colors, &SearchOptions::default()).outcome;
+                for propagation in [Propagation::None, Propagation::ForwardChecking]{
+                    let options = SearchOptions{propagation, backjumping: true, ..SearchOptions::default()};
+                    assert_eq!(search(&mut graph, num_colors, &options).outcome, expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn symmetry_breaking_shrinks_the_tree(){
+        //proving K6 needs 6 colors tries every injective coloring of the first
+        //five vertices, unless the colors are interchangeable
+        let mut graph = generators::queen_graph(1, 6);
+        let plain = search(&mut graph, 5, &SearchOptions::default());
+        let options = SearchOptions{break_symmetry: true, ..SearchOptions::default()};
+        let broken = search(&mut graph, 5, &options);
+        assert_eq!(broken.outcome, Outcome::NoSolution);
+        assert_eq!(plain.nodes, 5 + 5 * 4 + 5 * 4 * 3 + 5 * 4 * 3 * 2 + 5 * 4 * 3 * 2);
+        assert_eq!(broken.nodes, 5);
+
+        //a coloring found with value precedence opens its colors in search order
+        let mut graph = generators::mycielski(4);
+        assert_eq!(search(&mut graph, 5, &options).outcome, Outcome::Colored);
+        let mut next = 0;
+        for node in &graph{
+            let color = node.color.unwrap().index();
+            assert!(color <= next);
+            next = next.max(color + 1);
+        }
+    }
+
+    #[test]
+    fn given_order(){
+        let mut graph = generators::mycielski(3);
+        let order = parse_order("11 10 9 8 7 6 5 4 3 2 1", 11).unwrap();
+        assert_eq!(order[0], 10);
+        let options = SearchOptions{var_order: VarOrder::Given(order), ..SearchOptions::default()};
+        assert_eq!(search(&mut graph, 4, &options).outcome, Outcome::Colored);
+        assert!(all_consistent(&graph));
+
+        assert!(parse_order("1 2", 3).is_err());
+        assert!(parse_order("1 2 2", 3).is_err());
+        assert!(parse_order("1 2 4", 3).is_err());
+        assert!(parse_order("1 x 3", 3).is_err());
+    }
+}
@@ -0,0 +1,113 @@
+//colorings in a DIMACS-style solution format: one "vertex color" line per
+//colored vertex, both 1-based, with optional "c" comment lines
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use crate::dimacs::ParseError;
+use crate::{Color, Node};
+
+pub fn write_solution<W: Write>(graph: &[Node], mut writer: W) -> io::Result<()>{
+    for (index, node) in graph.iter().enumerate(){
+        if let Some(color) = node.color{
+            writeln!(writer, "{} {}", index + 1, color.index() + 1)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn save_solution(graph: &[Node], filename: &str) -> io::Result<()>{
+    let mut writer = BufWriter::new(File::create(filename)?);
+    write_solution(graph, &mut writer)?;
+    writer.flush()
+}
+
+//assigns the colors in the solution to the nodes of graph; vertices that
+//are not mentioned are left uncolored
+pub fn parse_solution<R: BufRead>(reader: R, graph: &mut [Node]) -> Result<(), ParseError>{
+    for node in graph.iter_mut(){
+        node.color = None;
+    }
+    let mut seen = HashSet::new();
+    for (index, line) in reader.lines().enumerate(){
+        let line_no = index + 1;
+        let line_error = |message: String| ParseError::Line{line: line_no, message};
+        let line = line.map_err(|err| ParseError::Io(format!("line {}: {}", line_no, err)))?;
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        match fields.first(){
+            None | Some(&"c") => continue,
+            Some(_) => (),
+        }
+        if fields.len() != 2{
+            return Err(line_error(format!("expected \"vertex color\", got \"{}\"", line.trim())));
+        }
+        let vertex: usize = fields[0].parse().map_err(|_| line_error(format!("invalid vertex: {}", fields[0])))?;
+        let color: usize = fields[1].parse().map_err(|_| line_error(format!("invalid color: {}", fields[1])))?;
+        if vertex == 0 || vertex > graph.len(){
+            return Err(line_error(format!("vertex {} out of range 1..={}", vertex, graph.len())));
+        }
+        if color == 0{
+            return Err(line_error("colors start at 1".to_string()));
+        }
+        if !seen.insert(vertex){
+            return Err(line_error(format!("vertex {} colored twice", vertex)));
+        }
+        graph[vertex - 1].assign_color(Color(color - 1));
+    }
+    Ok(())
+}
+
+pub fn read_solution(filename: &str, graph: &mut [Node]) -> Result<(), ParseError>{
+    let file = File::open(filename).map_err(|err| ParseError::Io(format!("{}: {}", filename, err)))?;
+    parse_solution(BufReader::new(file), graph)
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use crate::check_coloring;
+
+    fn path_graph(num_nodes: usize) -> Vec<Node>{
+        let mut graph: Vec<Node> = (0..num_nodes).map(Node::new).collect();
+        for i in 1..num_nodes{
+            graph[i - 1].add_neighbor(i);
+            graph[i].add_neighbor(i - 1);
+        }
+        graph
+    }
+
+    #[test]
+    fn round_trip(){
+        let mut graph = path_graph(3);
+        graph[0].assign_color(Color(0));
+        graph[1].assign_color(Color(8));
+        graph[2].assign_color(Color(0));
+        let mut bytes = Vec::new();
+        write_solution(&graph, &mut bytes).unwrap();
+        assert_eq!(String::from_utf8(bytes.clone()).unwrap(), "1 1\n2 9\n3 1\n");
+
+        let mut other = path_graph(3);
+        parse_solution(&bytes[..], &mut other).unwrap();
+        let colors: Vec<Option<Color>> = other.iter().map(|node| node.color).collect();
+        assert_eq!(colors, vec![Some(Color(0)), Some(Color(8)), Some(Color(0))]);
+    }
+
+    #[test]
+    fn reports_conflicts_and_uncolored(){
+        let mut graph = path_graph(4);
+        parse_solution(&b"c from another solver\n1 2\n2 2\n\n4 1\n"[..], &mut graph).unwrap();
+        let report = check_coloring(&graph);
+        assert_eq!(report.conflicts, vec![(0, 1)]);
+        assert_eq!(report.uncolored, vec![2]);
+        assert!(!report.is_valid());
+    }
+
+    #[test]
+    fn solution_errors(){
+        let mut graph = path_graph(2);
+        assert!(parse_solution(&b"1\n"[..], &mut graph).is_err());
+        assert!(parse_solution(&b"3 1\n"[..], &mut graph).is_err());
+        assert!(parse_solution(&b"1 0\n"[..], &mut graph).is_err());
+        assert!(parse_solution(&b"1 1\n1 2\n"[..], &mut graph).is_err());
+        assert!(parse_solution(&b"1 x\n"[..], &mut graph).is_err());
+    }
+}
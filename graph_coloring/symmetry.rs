@@ -0,0 +1,52 @@
+//renaming the colors of a coloring gives another coloring, so the colorings
+//of a graph fall into orbits under the permutations of the colors. The
+//representative of an orbit numbers the colors in order of first use by
+//vertex index.
+use crate::{Color, Node};
+
+//renames the colors of graph to the representative of their orbit; uncolored
+//vertices stay uncolored
+pub fn canonicalize(graph: &mut [Node]){
+    let mut renamed: Vec<Option<Color>> = Vec::new();
+    let mut next = 0;
+    for node in graph.iter_mut(){
+        let color = match node.color{
+            Some(color) => color.index(),
+            None => continue,
+        };
+        if color >= renamed.len(){
+            renamed.resize(color + 1, None);
+        }
+        let new_color = *renamed[color].get_or_insert_with(|| {
+            next += 1;
+            Color(next - 1)
+        });
+        node.color = Some(new_color);
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use crate::{all_consistent, generators, search};
+
+    #[test]
+    fn renamed_colorings_share_a_representative(){
+        let mut graph = generators::mycielski(4);
+        search::search(&mut graph, 5, &search::SearchOptions::default());
+        let colors: Vec<Option<Color>> = graph.iter().map(|node| node.color).collect();
+        canonicalize(&mut graph);
+        assert!(all_consistent(&graph));
+        let canonical: Vec<Option<Color>> = graph.iter().map(|node| node.color).collect();
+        assert_eq!(canonical[0], Some(Color(0)));
+
+        //any permutation of the original colors lands on the same coloring
+        let permutation = [3, 0, 4, 1, 2];
+        for (node, color) in graph.iter_mut().zip(&colors){
+            node.color = color.map(|color| Color(permutation[color.index()]));
+        }
+        canonicalize(&mut graph);
+        let again: Vec<Option<Color>> = graph.iter().map(|node| node.color).collect();
+        assert_eq!(again, canonical);
+    }
+}
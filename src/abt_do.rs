@@ -0,0 +1,223 @@
+// ABT with dynamic ordering (Zivan and Meisels). The order between agents is
+// no longer fixed by id: an agent that receives a nogood and has to change its
+// value reorders the agents below it, fewest free columns first given the
+// queens down to itself. Orders travel in Order messages together with a
+// counter per position; an agent adopts an order when its counters are
+// lexicographically larger than those of the order it holds, so all agents
+// settle on the same one.
+use std::cmp::Reverse;
+use crate::awc::higher;
+use crate::{consistent, AgentState, Board, Message, Position, ID};
+
+// queue a message, never letting an Ok overwrite a nogood or an order; the Ok
+// then goes out with the next round instead
+fn post(state: &mut AgentState, to: ID, message: Message) {
+    match (&state.mess2send[to], &message) {
+        (Message::Nogood(..), Message::Ok(..)) | (Message::Order(..), Message::Ok(..)) => {
+            state.pending_ok = true;
+            return;
+        },
+        (Message::Ok(..), _) => state.pending_ok = true,
+        _ => (),
+    }
+    state.mess2send[to] = message;
+}
+
+fn send_oks(state: &mut AgentState) {
+    let pos = state.pos[state.id];
+    for i in 0..state.mess2send.len() {
+        if i != state.id {
+            post(state, i, Message::Ok(state.id, pos, 0));
+        }
+    }
+}
+
+// the agents from the highest priority down
+fn order(state: &AgentState) -> Vec<ID> {
+    let mut order: Vec<ID> = (0..state.priorities.len()).collect();
+    order.sort_by_key(|&i| (Reverse(state.priorities[i]), i));
+    order
+}
+
+fn set_order(state: &mut AgentState, order: &[ID]) {
+    let num_agents = order.len();
+    for (position, &agent) in order.iter().enumerate() {
+        state.priorities[agent] = num_agents - position;
+    }
+}
+
+// sort the agents below us by the number of columns the queens down to us
+// leave them, bumping our counter and clearing the ones below
+fn reorder(state: &mut AgentState) {
+    let mut order = order(state);
+    let num_agents = order.len();
+    let me = order.iter().position(|&i| i == state.id).unwrap();
+    let (above, below) = order.split_at_mut(me + 1);
+    let free_cols = |j: ID| (0..num_agents).filter(|&col| above.iter().all(|&i| {
+        consistent(i, state.pos[i], j, Position::Col(col))
+    })).count();
+    below.sort_by_cached_key(|&j| (free_cols(j), j));
+    state.order_stamps[me] += 1;
+    for stamp in &mut state.order_stamps[(me + 1)..] {
+        *stamp = 0;
+    }
+    set_order(state, &order);
+    for &i in &order[(me + 1)..] {
+        post(state, i, Message::Order(order.clone(), state.order_stamps.clone()));
+    }
+}
+
+fn allowed(state: &AgentState, col: usize) -> bool {
+    for i in 0..state.pos.len() {
+        if i != state.id && higher(state, i, state.id)
+                && !consistent(i, state.pos[i], state.id, Position::Col(col)) {
+            return false;
+        }
+    }
+    state.no_goods.find_where(col, &state.pos, |i| higher(state, i, state.id)).is_none()
+}
+
+// as explain in main.rs, but only agents above us can be culprits, and the
+// highest one attacking a column is blamed for it
+fn explain(state: &AgentState, num_agents: usize) -> Board {
+    let above: Vec<ID> = order(state).into_iter()
+        .take_while(|&i| i != state.id).collect();
+    let mut nogood = Board::Board(vec![Position::Unass; num_agents]);
+    for col in 0..state.domain {
+        let attacker = above.iter().find(|&&i| {
+            !consistent(i, state.pos[i], state.id, Position::Col(col))
+        });
+        if let Some(&i) = attacker {
+            nogood[i] = state.pos[i];
+            continue;
+        }
+        match state.no_goods.find_where(col, &state.pos, |i| higher(state, i, state.id)) {
+            Some(pairs) => for (i, col) in pairs {nogood[i] = Position::Col(col);},
+            None => for &i in &above {nogood[i] = state.pos[i];},
+        }
+    }
+    nogood
+}
+
+// one ABT_DO step on the agent's current view; fills mess2send
+pub fn run_agent(state: &mut AgentState, num_agents: usize) {
+    if state.pending_ok {
+        state.pending_ok = false;
+        send_oks(state);
+    }
+    let current = match state.pos[state.id] {
+        Position::Col(col) => allowed(state, col),
+        Position::Unass => false,
+    };
+    if current {
+        // a nogood that did not rule out our value came from an agent with
+        // another order; it forgot our value when it sent it
+        if state.got_nogood {
+            state.got_nogood = false;
+            send_oks(state);
+        }
+    } else {
+        loop {
+            if let Some(col) = (0..state.domain).find(|&col| allowed(state, col)) {
+                state.pos[state.id] = Position::Col(col);
+                send_oks(state);
+                if state.got_nogood {
+                    state.got_nogood = false;
+                    reorder(state);
+                }
+                break;
+            }
+            let nogood = explain(state, num_agents);
+            // the nogood goes to its lowest priority agent
+            let target = order(state).into_iter().rev()
+                .find(|&i| nogood[i] != Position::Unass);
+            let target = match target {
+                Some(target) => target,
+                None => {
+                    state.no_solution = true;
+                    break;
+                },
+            };
+            post(state, target, Message::Nogood(state.id, nogood));
+            state.pos[target] = Position::Unass;
+        }
+    }
+    // whoever hears nothing from us this round must still see it as busy
+    if state.mess2send.iter().any(|m| !matches!(m, Message::Idle)) {
+        for i in 0..num_agents {
+            if let Message::Idle = state.mess2send[i] {
+                state.mess2send[i] = Message::Empty;
+            }
+        }
+    }
+}
+
+// receive one message from every agent; returns idle iff all were idle
+pub fn receive_messages(num_agents: usize, state: &mut AgentState) -> bool {
+    let mut idle = true;
+    for _ in 0..num_agents {
+        match state.rx.recv().unwrap() {
+            Message::Idle => (),
+            Message::Empty => idle = false,
+            Message::Ok(sender, pos, _) => {
+                idle = false;
+                if let Position::Col(col) = pos {
+                    state.no_goods.remove_obsolete(sender, col);
+                }
+                state.pos[sender] = pos;
+            },
+            Message::Nogood(_, nogood) => {
+                idle = false;
+                if nogood[state.id] != state.pos[state.id]
+                        || !state.no_goods.compatible(&nogood, &state.pos) {
+                    // obsolete; the sender has forgotten our value, so
+                    // repeat it
+                    state.pending_ok = true;
+                    continue;
+                }
+                // agents we have not heard from yet take the nogood's values
+                for i in 0..num_agents {
+                    if state.pos[i] == Position::Unass {
+                        state.pos[i] = nogood[i];
+                    }
+                }
+                state.no_goods.insert(&nogood);
+                state.got_nogood = true;
+            },
+            Message::Order(order, stamps) => {
+                idle = false;
+                if stamps > state.order_stamps {
+                    set_order(state, &order);
+                    state.order_stamps = stamps;
+                }
+            },
+            Message::Token(..) | Message::Stop => unreachable!(),
+        }
+    }
+    idle
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::Algorithm;
+    use crate::solve;
+
+    #[test]
+    fn abt_do_solves_boards() {
+        for n in [1, 4, 5, 8, 12] {
+            for break_symmetry in [false, true] {
+                let result = solve(n, Some(10_000), Algorithm::AbtDo, break_symmetry);
+                assert!(result.solved, "no solution for n = {}", n);
+            }
+        }
+    }
+
+    #[test]
+    fn abt_do_finds_no_solution() {
+        for n in [2, 3] {
+            let result = solve(n, Some(10_000), Algorithm::AbtDo, true);
+            assert!(!result.solved);
+            assert!(result.rounds < 10_000);
+        }
+    }
+}
@@ -0,0 +1,285 @@
+// ABT without rounds, as Yokoo states it: every agent blocks on its channel
+// and handles each Ok and Nogood as it arrives. An agent only sends when its
+// value changes, when it has to backtrack, or when a nogood shows that the
+// sender has forgotten its value. No agent ever waits for the others, so
+// there is no round in which everybody can see that the run is over.
+//
+// Termination is detected with Safra's token algorithm. Every agent counts
+// the Ok and Nogood messages it sent minus the ones it received, and turns
+// black when it receives one. Agent 0 sends a token around the ring of
+// agents, each adding its count and its color and turning white again; the
+// agents only act on messages, so they are passive whenever they hold the
+// token and pass it on at once. When the token comes back white, agent 0 is
+// white and the counts add up to zero, no message is on its way and nobody
+// is working, and agent 0 tells everyone to stop. Otherwise it sends a new
+// token.
+use std::cmp;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use crate::{column_culprits, explain, is_solution, make_agents, AgentState, Board, Message, Position,
+            RunResult, ID};
+
+struct Agent {
+    state: AgentState,
+    // Ok and Nogood messages sent minus the ones received, and whether one
+    // was received since the token last passed
+    balance: isize,
+    black: bool,
+    // Ok and Nogood messages sent, tokens and Stops sent to detect the end,
+    // and messages handled
+    sent: usize,
+    detection: usize,
+    steps: usize,
+}
+
+impl Agent {
+    // the receiver may be gone once the run is stopping
+    fn send(&mut self, to: ID, message: Message) {
+        self.balance += 1;
+        self.sent += 1;
+        let _ = self.state.txs[to].send(message);
+    }
+
+    // the Stops count towards detection whatever the reason to stop
+    fn stop_all(&mut self) {
+        let id = self.state.id;
+        for (i, tx) in self.state.txs.iter().enumerate() {
+            if i != id {
+                let _ = tx.send(Message::Stop);
+                self.detection += 1;
+            }
+        }
+    }
+
+    fn send_token(&mut self, balance: isize, black: bool) {
+        let next = (self.state.id + 1) % self.state.txs.len();
+        self.detection += 1;
+        self.black = false;
+        let _ = self.state.txs[next].send(Message::Token(balance, black));
+    }
+
+    // returns whether agent 0 saw that the run is over
+    fn pass_token(&mut self, balance: isize, black: bool) -> bool {
+        if self.state.id != 0 {
+            self.send_token(balance + self.balance, black || self.black);
+            return false;
+        }
+        if !black && !self.black && balance + self.balance == 0 {
+            self.stop_all();
+            return true;
+        }
+        self.send_token(0, false);
+        false
+    }
+
+    fn send_oks(&mut self) {
+        let (id, pos) = (self.state.id, self.state.pos[self.state.id]);
+        for succ in (id + 1)..self.state.txs.len() {
+            self.send(succ, Message::Ok(id, pos, 0));
+        }
+    }
+
+    fn allowed(&self, col: usize) -> bool {
+        column_culprits(&self.state, col).is_none()
+    }
+
+    // keeps the current column if the view allows it, otherwise takes the
+    // first one allowed, backtracking until there is one
+    fn check_agent_view(&mut self) {
+        let id = self.state.id;
+        let old = self.state.pos[id];
+        if matches!(old, Position::Col(col) if self.allowed(col)) {return;}
+        loop {
+            if let Some(col) = (0..self.state.domain).find(|&col| self.allowed(col)) {
+                self.state.pos[id] = Position::Col(col);
+                break;
+            }
+            let nogood = explain(&self.state);
+            if nogood.len() == 0 {
+                self.state.no_solution = true;
+                return;
+            }
+            let pred = nogood.len() - 1;
+            self.send(pred, Message::Nogood(id, nogood));
+            self.state.pos[pred] = Position::Unass;
+        }
+        if self.state.pos[id] != old {
+            self.send_oks();
+        }
+    }
+
+    fn handle(&mut self, message: Message) {
+        let id = self.state.id;
+        match message {
+            Message::Ok(sender, pos, _) => {
+                if let Position::Col(col) = pos {
+                    self.state.no_goods.remove_obsolete(sender, col);
+                }
+                self.state.pos[sender] = pos;
+                self.check_agent_view();
+            },
+            Message::Nogood(sender, nogood) => {
+                let pos = self.state.pos[id];
+                if nogood[id] != pos {
+                    // obsolete: our new value is already on its way
+                    return;
+                }
+                // agents we have forgotten take the nogood's values until
+                // they tell us otherwise
+                for i in 0..nogood.len() {
+                    if i != id && self.state.pos[i] == Position::Unass {
+                        self.state.pos[i] = nogood[i];
+                    }
+                }
+                if self.state.no_goods.compatible(&nogood, &self.state.pos) {
+                    self.state.no_goods.insert(&nogood);
+                    self.check_agent_view();
+                }
+                // the sender dropped our value when it sent the nogood
+                if self.state.pos[id] == pos {
+                    self.send(sender, Message::Ok(id, pos, 0));
+                }
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    fn run(&mut self, max_steps: Option<usize>, no_solution: &AtomicBool) {
+        let id = self.state.id;
+        for i in 0..self.state.pos.len() {
+            if i != id {
+                self.state.pos[i] = Position::Unass;
+            }
+        }
+        self.check_agent_view();
+        self.send_oks();
+        if id == 0 {
+            self.send_token(0, false);
+        }
+        loop {
+            let message = match self.state.rx.recv().unwrap() {
+                Message::Stop => break,
+                Message::Token(balance, black) => match self.pass_token(balance, black) {
+                    true => break,
+                    false => continue,
+                },
+                message => message,
+            };
+            if max_steps.is_some_and(|max| self.steps >= max) {
+                self.stop_all();
+                break;
+            }
+            self.balance -= 1;
+            self.black = true;
+            self.steps += 1;
+            self.handle(message);
+            if self.state.no_solution {
+                no_solution.store(true, Ordering::SeqCst);
+                self.stop_all();
+                break;
+            }
+        }
+    }
+}
+
+// runs one thread per agent until no message is left, an agent proves there
+// is no solution, or one agent has handled max_steps messages; rounds in the
+// result is the most messages one agent handled, reported as steps
+pub fn solve(num_agents: usize, max_steps: Option<usize>, break_symmetry: bool) -> RunResult {
+    let states = make_agents(num_agents, break_symmetry);
+    let no_solution = AtomicBool::new(false);
+    let mut board = Board::Board(vec![Position::Unass; num_agents]);
+    let (mut rounds, mut messages, mut detection_messages) = (0, 0, 0);
+    let (mut nogoods, mut peak_nogoods) = (0, 0);
+    thread::scope(|scope| {
+        let handles: Vec<_> = states.into_iter().map(|state| {
+            let no_solution = &no_solution;
+            scope.spawn(move || {
+                let mut agent = Agent { state, balance: 0, black: false, sent: 0, detection: 0, steps: 0 };
+                agent.run(max_steps, no_solution);
+                agent
+            })
+        }).collect();
+        for handle in handles {
+            let agent = handle.join().unwrap();
+            let id = agent.state.id;
+            board[id] = agent.state.pos[id];
+            rounds = cmp::max(rounds, agent.steps);
+            messages += agent.sent;
+            detection_messages += agent.detection;
+            nogoods += agent.state.no_goods.len();
+            peak_nogoods = cmp::max(peak_nogoods, agent.state.no_goods.peak());
+        }
+    });
+    let solved = !no_solution.load(Ordering::SeqCst) && is_solution(&board);
+    RunResult { board, rounds, solved, nogoods, peak_nogoods, messages, detection_messages }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agents(num_agents: usize) -> Vec<Agent> {
+        make_agents(num_agents, false).into_iter().map(|state| {
+            Agent { state, balance: 0, black: false, sent: 0, detection: 0, steps: 0 }
+        }).collect()
+    }
+
+    #[test]
+    fn token_collects_counts_and_colors() {
+        let mut agents = agents(3);
+        agents[1].balance = 2;
+        agents[1].black = true;
+        assert!(!agents[1].pass_token(-1, false));
+        assert!(!agents[1].black);
+        assert!(matches!(agents[2].state.rx.try_recv(), Ok(Message::Token(1, true))));
+    }
+
+    #[test]
+    fn termination_needs_a_clean_lap() {
+        let mut agents = agents(3);
+        let agent = &mut agents[0];
+        agent.balance = 2;
+        // a message is still on its way
+        assert!(!agent.pass_token(-1, false));
+        // somebody received one while the token went round
+        assert!(!agent.pass_token(-2, true));
+        agent.black = true;
+        assert!(!agent.pass_token(-2, false));
+        assert!(agent.pass_token(-2, false));
+        // three new tokens and the Stops to the other two
+        assert_eq!(agent.detection, 5);
+    }
+
+    #[test]
+    fn async_abt_solves_boards() {
+        for n in [1, 4, 5, 8, 10, 16] {
+            for break_symmetry in [false, true] {
+                let result = solve(n, None, break_symmetry);
+                assert!(result.solved, "no solution for n = {}", n);
+                // at least one lap of the token and the Stops
+                assert!(result.detection_messages >= 2 * n - 1);
+                if break_symmetry {
+                    assert!(matches!(result.board[0], Position::Col(col) if col <= (n - 1) / 2));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn async_abt_finds_no_solution() {
+        for n in [2, 3] {
+            for break_symmetry in [false, true] {
+                assert!(!solve(n, None, break_symmetry).solved);
+            }
+        }
+    }
+
+    #[test]
+    fn step_limit_stops_the_agents() {
+        let result = solve(12, Some(3), false);
+        assert!(result.rounds <= 3);
+        // the agent that stopped told the other eleven
+        assert!(result.detection_messages >= 11);
+    }
+}
@@ -0,0 +1,164 @@
+// Asynchronous Weak-Commitment search (Yokoo). Unlike ABT the order between
+// agents is not fixed by id: every agent carries a priority in its Ok
+// messages, and an agent that cannot find a value consistent with the
+// agents above it records a nogood and jumps above all of them.
+use crate::{consistent, AgentState, Board, Message, Position, ID};
+
+// a is above b if it has the larger priority, ties go to the smaller id
+pub fn higher(state: &AgentState, a: ID, b: ID) -> bool {
+    let (pa, pb) = (state.priorities[a], state.priorities[b]);
+    pa > pb || (pa == pb && a < b)
+}
+
+// does col for this agent break a nogood whose other agents are all above it
+fn ruled_out(state: &AgentState, col: usize) -> bool {
+    state.no_goods.find_where(col, &state.pos, |i| higher(state, i, state.id)).is_some()
+}
+
+// number of agents attacking col; only the ones below us unless all is set
+fn conflicts(state: &AgentState, col: usize, all: bool) -> usize {
+    (0..state.pos.len()).filter(|&i| {
+        i != state.id && (all || !higher(state, i, state.id))
+            && !consistent(i, state.pos[i], state.id, Position::Col(col))
+    }).count()
+}
+
+fn consistent_with_higher(state: &mut AgentState, col: usize) -> bool {
+    for i in 0..state.pos.len() {
+        if i != state.id && higher(state, i, state.id)
+                && !consistent(i, state.pos[i], state.id, Position::Col(col)) {
+            return false;
+        }
+    }
+    !ruled_out(state, col)
+}
+
+fn next_random(state: &mut AgentState) -> u64 {
+    // xorshift64
+    state.rng ^= state.rng << 13;
+    state.rng ^= state.rng >> 7;
+    state.rng ^= state.rng << 17;
+    state.rng
+}
+
+// the candidate with the fewest conflicts, keeping the current column when it
+// is among the best and breaking other ties at random
+fn min_conflict(state: &mut AgentState, candidates: &[usize], all: bool) -> usize {
+    let counts: Vec<usize> = candidates.iter().map(|&col| conflicts(state, col, all)).collect();
+    let best = *counts.iter().min().unwrap();
+    let best_cols: Vec<usize> = candidates.iter().zip(&counts)
+        .filter(|(_, &count)| count == best).map(|(&col, _)| col).collect();
+    if let Position::Col(col) = state.pos[state.id] {
+        if best_cols.contains(&col) {return col;}
+    }
+    let pick = next_random(state) as usize % best_cols.len();
+    best_cols[pick]
+}
+
+fn send_oks(state: &mut AgentState) {
+    let pos = state.pos[state.id];
+    let priority = state.priorities[state.id];
+    for i in 0..state.mess2send.len() {
+        state.mess2send[i] = Message::Ok(state.id, pos, priority);
+    }
+    // every agent, ourselves included, must see this round as busy so that
+    // they all agree on when to stop
+    state.mess2send[state.id] = Message::Empty;
+}
+
+// one AWC step on the agent's current view; fills mess2send
+pub fn run_agent(state: &mut AgentState, num_agents: usize) {
+    // members of our last nogood got the nogood instead of our new value
+    if state.pending_ok {
+        state.pending_ok = false;
+        send_oks(state);
+    }
+    if let Position::Col(col) = state.pos[state.id] {
+        if consistent_with_higher(state, col) {return;}
+    }
+
+    let candidates: Vec<usize> = (0..state.domain)
+        .filter(|&col| consistent_with_higher(state, col)).collect();
+    if !candidates.is_empty() {
+        let col = min_conflict(state, &candidates, false);
+        state.pos[state.id] = Position::Col(col);
+        send_oks(state);
+        return;
+    }
+
+    // no value works with the agents above us: their view is a nogood
+    let mut nogood = Board::Board(vec![Position::Unass; num_agents]);
+    for i in 0..num_agents {
+        if i != state.id && higher(state, i, state.id) {
+            nogood[i] = state.pos[i];
+        }
+    }
+    let members: Vec<ID> = (0..num_agents).filter(|&i| nogood[i] != Position::Unass).collect();
+    if members.is_empty() {
+        state.no_solution = true;
+        return;
+    }
+    if state.sent_no_goods.contains(&nogood) {
+        // an old nogood: wait for the others to move
+        return;
+    }
+    state.sent_no_goods.push(nogood.clone());
+
+    state.priorities[state.id] = state.priorities.iter().max().unwrap() + 1;
+    let all_cols: Vec<usize> = (0..state.domain).collect();
+    let col = min_conflict(state, &all_cols, true);
+    state.pos[state.id] = Position::Col(col);
+    send_oks(state);
+    for i in members {
+        state.mess2send[i] = Message::Nogood(state.id, nogood.clone());
+    }
+    state.pending_ok = true;
+}
+
+// receive one message from every agent, updating positions and priorities
+// returns idle iff it receives idle from every other agent
+pub fn receive_messages(num_agents: usize, state: &mut AgentState) -> bool {
+    let mut idle = true;
+    for _ in 0..num_agents {
+        match state.rx.recv().unwrap() {
+            Message::Idle => (),
+            Message::Empty => idle = false,
+            Message::Ok(sender, pos, priority) => {
+                idle = false;
+                state.pos[sender] = pos;
+                state.priorities[sender] = priority;
+            },
+            Message::Nogood(_, nogood) => {
+                idle = false;
+                state.no_goods.insert(&nogood);
+            },
+            Message::Order(..) | Message::Token(..) | Message::Stop => unreachable!(),
+        }
+    }
+    idle
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::Algorithm;
+    use crate::solve;
+
+    #[test]
+    fn awc_solves_boards() {
+        for n in [1, 4, 8, 16, 30] {
+            for break_symmetry in [false, true] {
+                let result = solve(n, Some(10_000), Algorithm::Awc, break_symmetry);
+                assert!(result.solved, "no solution for n = {}", n);
+            }
+        }
+    }
+
+    #[test]
+    fn awc_finds_no_solution() {
+        for n in [2, 3] {
+            let result = solve(n, Some(10_000), Algorithm::Awc, true);
+            assert!(!result.solved);
+            assert!(result.rounds < 10_000);
+        }
+    }
+}
@@ -0,0 +1,209 @@
+// Backtracking n-queens with the attacked squares of the next row kept in
+// machine words: one bit per column for the columns and for the two
+// diagonal directions, shifted by one column per row. This is the usual fast
+// single-machine solver, for boards of up to 64 columns, and the baseline the
+// agent runs are measured against. The parallel versions split the search on
+// the column of the first row's queen.
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use crate::{Board, Position, RunResult};
+
+pub const MAX_SIZE: usize = 64;
+
+fn mask(n: usize) -> u64 {
+    if n == 64 {u64::MAX} else {(1 << n) - 1}
+}
+
+// a search on an n by n board; cols, diag and anti passed down are the
+// columns of the next row that the queens above attack straight down and
+// along either diagonal
+struct Search {
+    n: usize,
+    mask: u64,
+    nodes: usize,// queens placed
+}
+
+impl Search {
+    // extends placed to a full solution; gives up when stop is set
+    fn first(&mut self, placed: &mut Vec<usize>, cols: u64, diag: u64, anti: u64, stop: &AtomicBool) -> bool {
+        if placed.len() == self.n {return true;}
+        if stop.load(Ordering::Relaxed) {return false;}
+        let mut free = !(cols | diag | anti) & self.mask;
+        while free != 0 {
+            let bit = free & free.wrapping_neg();
+            free ^= bit;
+            self.nodes += 1;
+            placed.push(bit.trailing_zeros() as usize);
+            if self.first(placed, cols | bit, (diag | bit) << 1, (anti | bit) >> 1, stop) {
+                return true;
+            }
+            placed.pop();
+        }
+        false
+    }
+
+    fn count(&mut self, row: usize, cols: u64, diag: u64, anti: u64) -> u64 {
+        if row == self.n {return 1;}
+        let mut free = !(cols | diag | anti) & self.mask;
+        let mut total = 0;
+        while free != 0 {
+            let bit = free & free.wrapping_neg();
+            free ^= bit;
+            self.nodes += 1;
+            total += self.count(row + 1, cols | bit, (diag | bit) << 1, (anti | bit) >> 1);
+        }
+        total
+    }
+}
+
+fn to_board(cols: &[usize], n: usize) -> Board {
+    let mut board = Board::Board(vec![Position::Unass; n]);
+    for (row, col) in cols.iter().enumerate() {
+        board[row] = Position::Col(*col);
+    }
+    board
+}
+
+// the first solution with the first queen in column first, if there is one
+fn first_with(n: usize, first: usize, stop: &AtomicBool) -> (Option<Vec<usize>>, usize) {
+    let mut search = Search { n, mask: mask(n), nodes: 1 };
+    let bit = 1 << first;
+    let mut placed = vec![first];
+    match search.first(&mut placed, bit, bit << 1, bit >> 1, stop) {
+        true => (Some(placed), search.nodes),
+        false => (None, search.nodes),
+    }
+}
+
+// solutions with the first queen in column first
+fn count_with(n: usize, first: usize) -> u64 {
+    let mut search = Search { n, mask: mask(n), nodes: 1 };
+    let bit = 1 << first;
+    search.count(1, bit, bit << 1, bit >> 1)
+}
+
+// a mirror image moves the first queen from column c to n - 1 - c, so the
+// left half counts twice and the middle column of an odd board once
+fn weight(n: usize, first: usize) -> u64 {
+    if 2 * first + 1 == n {1} else {2}
+}
+
+// the first solution in lexicographic order; rounds in the result counts the
+// queens placed
+pub fn solve(n: usize) -> RunResult {
+    let stop = AtomicBool::new(false);
+    let mut nodes = 0;
+    for first in 0..n {
+        let (cols, first_nodes) = first_with(n, first, &stop);
+        nodes += first_nodes;
+        if let Some(cols) = cols {
+            return RunResult { board: to_board(&cols, n), rounds: nodes, solved: true, nogoods: 0, peak_nogoods: 0, messages: 0, detection_messages: 0 };
+        }
+    }
+    RunResult { board: to_board(&[], n), rounds: nodes, solved: false, nogoods: 0, peak_nogoods: 0, messages: 0, detection_messages: 0 }
+}
+
+// as solve, with the first row's columns handed out to num_threads threads;
+// the first thread to find a solution stops the others, so which solution
+// comes back depends on timing
+pub fn solve_parallel(n: usize, num_threads: usize) -> RunResult {
+    let next = AtomicUsize::new(0);
+    let stop = AtomicBool::new(false);
+    let nodes = AtomicUsize::new(0);
+    let solution = Mutex::new(None);
+    thread::scope(|scope| {
+        for _ in 0..num_threads {
+            scope.spawn(|| loop {
+                let first = next.fetch_add(1, Ordering::SeqCst);
+                if first >= n || stop.load(Ordering::SeqCst) {break;}
+                let (cols, first_nodes) = first_with(n, first, &stop);
+                nodes.fetch_add(first_nodes, Ordering::Relaxed);
+                if let Some(cols) = cols {
+                    stop.store(true, Ordering::SeqCst);
+                    *solution.lock().unwrap() = Some(cols);
+                    break;
+                }
+            });
+        }
+    });
+    let rounds = nodes.into_inner();
+    match solution.into_inner().unwrap() {
+        Some(cols) => RunResult { board: to_board(&cols, n), rounds, solved: true, nogoods: 0, peak_nogoods: 0, messages: 0, detection_messages: 0 },
+        None => RunResult { board: to_board(&[], n), rounds, solved: false, nogoods: 0, peak_nogoods: 0, messages: 0, detection_messages: 0 },
+    }
+}
+
+// the number of solutions
+pub fn count(n: usize) -> u64 {
+    (0..n.div_ceil(2)).map(|first| weight(n, first) * count_with(n, first)).sum()
+}
+
+pub fn count_parallel(n: usize, num_threads: usize) -> u64 {
+    let next = AtomicUsize::new(0);
+    let total = AtomicU64::new(0);
+    thread::scope(|scope| {
+        for _ in 0..num_threads {
+            scope.spawn(|| loop {
+                let first = next.fetch_add(1, Ordering::SeqCst);
+                if first >= n.div_ceil(2) {break;}
+                total.fetch_add(weight(n, first) * count_with(n, first), Ordering::Relaxed);
+            });
+        }
+    });
+    total.into_inner()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::is_solution;
+
+    // OEIS A000170 for n = 1..12
+    const COUNTS: [u64; 12] = [1, 0, 0, 2, 10, 4, 40, 92, 352, 724, 2680, 14200];
+
+    #[test]
+    fn finds_solutions() {
+        for n in 1..=20 {
+            let result = solve(n);
+            assert_eq!(result.solved, n != 2 && n != 3, "n = {}", n);
+            if result.solved {
+                assert!(is_solution(&result.board));
+                let parallel = solve_parallel(n, 4);
+                assert!(parallel.solved && is_solution(&parallel.board));
+            }
+        }
+        let result = solve(8);
+        assert_eq!(result.board, to_board(&[0, 4, 7, 5, 2, 6, 1, 3], 8));
+        assert!(!solve_parallel(3, 2).solved);
+    }
+
+    #[test]
+    fn counts_match_oeis() {
+        for n in 1..=COUNTS.len() {
+            assert_eq!(count(n), COUNTS[n - 1], "n = {}", n);
+            assert_eq!(count_parallel(n, 3), COUNTS[n - 1], "n = {}", n);
+        }
+    }
+
+    #[test]
+    fn full_width_board() {
+        // the explicit solution for even n with n % 6 != 2; the search has
+        // to complete it from its first rows, and the bit of the last column
+        // must leave the word when a diagonal shifts instead of wrapping
+        let n = MAX_SIZE;
+        let solution: Vec<usize> = (0..n / 2).map(|i| 2 * i + 1).chain((0..n / 2).map(|i| 2 * i)).collect();
+        let mut placed = solution[..n - 4].to_vec();
+        let (mut cols, mut diag, mut anti) = (0, 0, 0);
+        for &col in &placed {
+            let bit = 1 << col;
+            cols |= bit;
+            diag = (diag | bit) << 1;
+            anti = (anti | bit) >> 1;
+        }
+        let mut search = Search { n, mask: mask(n), nodes: 0 };
+        assert!(search.first(&mut placed, cols, diag, anti, &AtomicBool::new(false)));
+        assert!(is_solution(&to_board(&placed, n)));
+        assert_eq!(mask(n), u64::MAX);
+    }
+}
@@ -0,0 +1,242 @@
+// command line handling for the n-queens ABT binary
+use crate::bitboard;
+
+pub const USAGE: &str = "usage: project [options]
+options:
+    -n, --size <n>          board size / number of agents (default 12)
+    --alg <abt|abt-do|awc|minconf|bitboard|bitboard-par>
+                            asynchronous backtracking, with dynamic agent
+                            ordering, weak-commitment search, min-conflicts
+                            local search or plain backtracking on bitmasks, on
+                            one thread or split on the first row (default abt)
+    --threads <n>           threads for bitboard-par (default 4)
+    --runtime <sync|async>  run the agents in lock-step rounds, or let them
+                            handle each message as it arrives (abt only;
+                            default async for abt, sync otherwise)
+    --max-rounds <n>        stop after n synchronous rounds, n messages handled
+                            by one asynchronous agent, or n moves for minconf
+                            (default: no limit, 100 n moves for minconf)
+    --seed <s>              seed for minconf (default 1)
+    --format <fmt>          board, columns or json (default board)
+    --break-symmetry        only let the first row's queen into the left half of
+                            the board, so mirror images are not searched twice
+                            (abt, abt-do and awc)
+    --canonical             print the solution as the representative of its
+                            orbit under the 8 symmetries of the board
+    --count                 count every solution by sequential search instead of
+                            looking for one; the bitboard solvers count on their
+                            own, the other algorithms are ignored
+    --all                   print every solution, then the count
+    --unique                with --count or --all, one solution per orbit under
+                            the 8 symmetries of the board
+    --time                  report wall-clock time, rounds (steps, the most
+                            messages one agent handled, for async) and
+                            messages, with the ones spent detecting termination";
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Algorithm {
+    Abt,
+    AbtDo,
+    Awc,
+    MinConflicts,
+    Bitboard,
+    BitboardParallel,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Runtime {
+    Sync,
+    Async,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Format {
+    Board,
+    Columns,
+    Json,
+}
+
+#[derive(Debug)]
+pub struct Config {
+    pub num_agents: usize,
+    pub algorithm: Algorithm,
+    pub max_rounds: Option<usize>,
+    pub seed: u64,
+    pub num_threads: usize,
+    pub runtime: Runtime,
+    pub format: Format,
+    pub timing: bool,
+    pub break_symmetry: bool,
+    pub canonical: bool,
+    pub count: bool,
+    pub list_all: bool,
+    pub unique: bool,
+}
+
+fn parse_format(name: &str) -> Result<Format, String> {
+    match name {
+        "board" => Ok(Format::Board),
+        "columns" => Ok(Format::Columns),
+        "json" => Ok(Format::Json),
+        _ => Err(format!("unknown format: {}", name)),
+    }
+}
+
+fn parse_runtime(name: Option<&String>) -> Result<Runtime, String> {
+    match name.map(String::as_str) {
+        Some("sync") => Ok(Runtime::Sync),
+        Some("async") => Ok(Runtime::Async),
+        Some(name) => Err(format!("unknown runtime: {}", name)),
+        None => Err("--runtime needs a value".to_string()),
+    }
+}
+
+fn parse_count(flag: &str, value: Option<&String>) -> Result<usize, String> {
+    let value = value.ok_or(format!("{} needs a value", flag))?;
+    match value.parse::<usize>() {
+        Ok(count) if count > 0 => Ok(count),
+        _ => Err(format!("{} expects a positive integer, got {}", flag, value)),
+    }
+}
+
+// args should not include the program name
+pub fn parse_args(args: &[String]) -> Result<Config, String> {
+    let mut config = Config {
+        num_agents: 12,
+        algorithm: Algorithm::Abt,
+        max_rounds: None,
+        seed: 1,
+        num_threads: 4,
+        runtime: Runtime::Sync,
+        format: Format::Board,
+        timing: false,
+        break_symmetry: false,
+        canonical: false,
+        count: false,
+        list_all: false,
+        unique: false,
+    };
+
+    let mut runtime = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-n" | "--size" => config.num_agents = parse_count(arg, iter.next())?,
+            "--alg" => {
+                config.algorithm = match iter.next().map(String::as_str) {
+                    Some("abt") => Algorithm::Abt,
+                    Some("abt-do") => Algorithm::AbtDo,
+                    Some("awc") => Algorithm::Awc,
+                    Some("minconf") => Algorithm::MinConflicts,
+                    Some("bitboard") => Algorithm::Bitboard,
+                    Some("bitboard-par") => Algorithm::BitboardParallel,
+                    Some(name) => return Err(format!("unknown algorithm: {}", name)),
+                    None => return Err("--alg needs a value".to_string()),
+                };
+            },
+            "--max-rounds" => config.max_rounds = Some(parse_count(arg, iter.next())?),
+            "--threads" => config.num_threads = parse_count(arg, iter.next())?,
+            "--runtime" => runtime = Some(parse_runtime(iter.next())?),
+            "--format" => {
+                let name = iter.next().ok_or("--format needs a value")?;
+                config.format = parse_format(name)?;
+            },
+            "--seed" => {
+                let seed = iter.next().ok_or("--seed needs a value")?;
+                config.seed = seed.parse().map_err(|_| format!("--seed expects an integer, got {}", seed))?;
+            },
+            "--time" => config.timing = true,
+            "--break-symmetry" => config.break_symmetry = true,
+            "--canonical" => config.canonical = true,
+            "--count" => config.count = true,
+            "--all" => config.list_all = true,
+            "--unique" => config.unique = true,
+            _ => return Err(format!("unknown argument: {}", arg)),
+        }
+    }
+    config.runtime = match (runtime, config.algorithm) {
+        (None, Algorithm::Abt) => Runtime::Async,
+        (Some(Runtime::Async), algorithm) if algorithm != Algorithm::Abt => {
+            return Err("--runtime async needs abt".to_string());
+        },
+        (runtime, _) => runtime.unwrap_or(Runtime::Sync),
+    };
+    let bitboard = matches!(config.algorithm, Algorithm::Bitboard | Algorithm::BitboardParallel);
+    if config.break_symmetry && (bitboard || config.algorithm == Algorithm::MinConflicts) {
+        return Err("--break-symmetry needs abt, abt-do or awc".to_string());
+    }
+    if bitboard && config.num_agents > bitboard::MAX_SIZE {
+        return Err(format!("the bitboard solvers handle boards up to {}", bitboard::MAX_SIZE));
+    }
+    if config.unique && !config.count && !config.list_all {
+        return Err("--unique needs --count or --all".to_string());
+    }
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn parse_defaults() {
+        let config = parse_args(&args("")).unwrap();
+        assert_eq!(config.num_agents, 12);
+        assert_eq!(config.algorithm, Algorithm::Abt);
+        assert_eq!(config.max_rounds, None);
+        assert_eq!(config.format, Format::Board);
+        assert!(!config.timing);
+        assert!(!config.break_symmetry && !config.canonical);
+        assert_eq!(config.runtime, Runtime::Async);
+        assert_eq!(parse_args(&args("--alg awc")).unwrap().runtime, Runtime::Sync);
+    }
+
+    #[test]
+    fn parse_options() {
+        let config = parse_args(&args("-n 20 --alg awc --max-rounds 500 --format json --time")).unwrap();
+        assert_eq!(config.num_agents, 20);
+        assert_eq!(config.algorithm, Algorithm::Awc);
+        assert_eq!(config.max_rounds, Some(500));
+        assert_eq!(config.format, Format::Json);
+        assert!(config.timing);
+
+        let config = parse_args(&args("--alg minconf --seed 9")).unwrap();
+        assert_eq!(config.algorithm, Algorithm::MinConflicts);
+        assert_eq!(config.seed, 9);
+
+        let config = parse_args(&args("--alg abt-do")).unwrap();
+        assert_eq!(config.algorithm, Algorithm::AbtDo);
+
+        let config = parse_args(&args("--break-symmetry --canonical")).unwrap();
+        assert!(config.break_symmetry && config.canonical);
+
+        let config = parse_args(&args("-n 30 --alg bitboard-par --threads 2")).unwrap();
+        assert_eq!(config.algorithm, Algorithm::BitboardParallel);
+        assert_eq!(config.num_threads, 2);
+
+        let config = parse_args(&args("-n 8 --count --unique")).unwrap();
+        assert!(config.count && config.unique && !config.list_all);
+
+        let config = parse_args(&args("--runtime sync")).unwrap();
+        assert_eq!(config.runtime, Runtime::Sync);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(parse_args(&args("-n 0")).is_err());
+        assert!(parse_args(&args("--size")).is_err());
+        assert!(parse_args(&args("--format xml")).is_err());
+        assert!(parse_args(&args("8")).is_err());
+        assert!(parse_args(&args("--alg dba")).is_err());
+        assert!(parse_args(&args("--alg minconf --break-symmetry")).is_err());
+        assert!(parse_args(&args("--unique")).is_err());
+        assert!(parse_args(&args("-n 65 --alg bitboard")).is_err());
+        assert!(parse_args(&args("--alg bitboard --threads 0")).is_err());
+        assert!(parse_args(&args("--runtime bsp")).is_err());
+        assert!(parse_args(&args("--alg awc --runtime async")).is_err());
+    }
+}
@@ -0,0 +1,125 @@
+// Every solution of the n-queens problem, by plain depth-first search with
+// the attacked columns and diagonals kept in flags. Solutions come out in
+// lexicographic order of their columns, row by row. Modulo the board
+// symmetries only the representative of each orbit is produced: it is the
+// smallest of its images, so its first queen is in the left half and the
+// search never looks at the right half of the first row.
+use crate::symmetry::canonical_columns;
+
+pub struct Solutions {
+    n: usize,
+    unique: bool,
+    cols: Vec<usize>,
+    // the next column to try in row cols.len()
+    next_col: usize,
+    col_used: Vec<bool>,
+    // indexed by row + col and by row + n - 1 - col
+    diag_used: Vec<bool>,
+    anti_used: Vec<bool>,
+}
+
+impl Solutions {
+    // all solutions for n >= 1, or one per orbit under the 8 symmetries if
+    // unique is set
+    pub fn new(n: usize, unique: bool) -> Solutions {
+        Solutions {
+            n,
+            unique,
+            cols: Vec::with_capacity(n),
+            next_col: 0,
+            col_used: vec![false; n],
+            diag_used: vec![false; 2 * n],
+            anti_used: vec![false; 2 * n],
+        }
+    }
+
+    fn free(&self, row: usize, col: usize) -> bool {
+        !self.col_used[col] && !self.diag_used[row + col] && !self.anti_used[row + self.n - 1 - col]
+    }
+
+    fn set(&mut self, row: usize, col: usize, used: bool) {
+        self.col_used[col] = used;
+        self.diag_used[row + col] = used;
+        self.anti_used[row + self.n - 1 - col] = used;
+    }
+
+    // takes the last queen off and returns its column
+    fn remove_last(&mut self) -> usize {
+        let col = self.cols.pop().unwrap();
+        self.set(self.cols.len(), col, false);
+        col
+    }
+
+    fn next_placement(&mut self) -> Option<Vec<usize>> {
+        loop {
+            let row = self.cols.len();
+            let limit = match row == 0 && self.unique {
+                true => self.n.div_ceil(2),
+                false => self.n,
+            };
+            match (self.next_col..limit).find(|&col| self.free(row, col)) {
+                Some(col) => {
+                    self.set(row, col, true);
+                    self.cols.push(col);
+                    self.next_col = 0;
+                    if self.cols.len() == self.n {
+                        let solution = self.cols.clone();
+                        self.next_col = self.remove_last() + 1;
+                        return Some(solution);
+                    }
+                },
+                None if row == 0 => {
+                    self.next_col = limit;
+                    return None;
+                },
+                None => self.next_col = self.remove_last() + 1,
+            }
+        }
+    }
+}
+
+impl Iterator for Solutions {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Vec<usize>> {
+        loop {
+            let cols = self.next_placement()?;
+            if !self.unique || canonical_columns(&cols) == cols {
+                return Some(cols);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::minconf::attack_free;
+    use crate::{Board, Position};
+
+    // OEIS A000170 and A002562 for n = 1..11
+    const ALL: [usize; 11] = [1, 0, 0, 2, 10, 4, 40, 92, 352, 724, 2680];
+    const UNIQUE: [usize; 11] = [1, 0, 0, 1, 2, 1, 6, 12, 46, 92, 341];
+
+    #[test]
+    fn counts_match_oeis() {
+        for n in 1..=ALL.len() {
+            assert_eq!(Solutions::new(n, false).count(), ALL[n - 1], "all solutions for n = {}", n);
+            assert_eq!(Solutions::new(n, true).count(), UNIQUE[n - 1], "unique solutions for n = {}", n);
+        }
+    }
+
+    #[test]
+    fn solutions_are_distinct_and_attack_free() {
+        let solutions: Vec<Vec<usize>> = Solutions::new(8, false).collect();
+        assert_eq!(solutions[0], vec![0, 4, 7, 5, 2, 6, 1, 3]);
+        assert!(solutions.windows(2).all(|pair| pair[0] < pair[1]));
+        for cols in &solutions {
+            assert!(attack_free(&Board::Board(cols.iter().map(|&col| Position::Col(col)).collect())));
+        }
+        // the iterator stays exhausted
+        let mut solutions = Solutions::new(4, false);
+        assert_eq!(solutions.by_ref().count(), 2);
+        assert_eq!(solutions.next(), None);
+    }
+}
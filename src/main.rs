@@ -1,7 +1,21 @@
 use std::{cmp, thread};
 use std::sync::{mpsc, Arc, Barrier};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::ops::{Index, IndexMut};
 use std::mem;
+use std::process;
+use std::time::{Duration, Instant};
+mod cli;
+mod awc;
+mod abt_do;
+mod minconf;
+mod nogood;
+mod symmetry;
+mod enumerate;
+mod bitboard;
+mod async_abt;
+use crate::cli::{Algorithm, Config, Format, Runtime};
+use crate::nogood::NogoodStore;
 
 #[derive(Clone, Debug, Copy, PartialEq)]
 enum Position {
@@ -9,7 +23,7 @@ enum Position {
     Col(usize),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 enum Board {
     Board(Vec<Position>),
 }
@@ -44,13 +58,21 @@ impl Index<usize> for Board {
 
 
 
-// a message can hold either an update position or a Nogood
+// a message can hold either an update position or a Nogood, both with the
+// sender's id, or tell the receiver that the sender is busy (Empty) or idle
+// this round. An Ok also carries the sender's priority, which only AWC
+// changes, and an Order carries a new agent order with its counters, for
+// ABT_DO. Token and Stop are how the agents of the asynchronous runtime find
+// out that the run is over
 #[derive(Debug, Clone)]
 enum Message {
-    Empty(usize),
-    Idle(usize),
-    Ok(usize, Position),
+    Empty,
+    Idle,
+    Ok(usize, Position, usize),
     Nogood(usize, Board),
+    Order(Vec<ID>, Vec<usize>),
+    Token(isize, bool),
+    Stop,
 }
 
 type ID = usize;
@@ -58,10 +80,36 @@ type ID = usize;
 struct AgentState {
     id: usize,
     pos: Board,
-    no_goods: Vec<Board>,
+    no_goods: NogoodStore,
     txs: Vec<mpsc::Sender<Message>>,
     rx: mpsc::Receiver<Message>,
     mess2send: Vec<Message>,
+    no_solution: bool,
+    // the agent tries columns 0..domain: all of them, or only the left half
+    // for the first row when breaking the mirror symmetry
+    domain: usize,
+    // the rest is only used by AWC and ABT_DO
+    priorities: Vec<usize>,
+    sent_no_goods: Vec<Board>,
+    pending_ok: bool,
+    rng: u64,
+    order_stamps: Vec<usize>,
+    got_nogood: bool,
+}
+
+// what a run of the agents produced
+struct RunResult {
+    board: Board,
+    rounds: usize,
+    solved: bool,
+    // nogoods held by all agents at the end, and the most one agent ever held
+    nogoods: usize,
+    peak_nogoods: usize,
+    // Ok, Nogood and Order messages the agents sent, and the ones they sent
+    // only to find out when to stop: Idle and Empty in synchronous rounds,
+    // tokens and Stops in the asynchronous runtime
+    messages: usize,
+    detection_messages: usize,
 }
     
 //checks for consistent queen placement
@@ -82,39 +130,31 @@ fn consistent(ar: ID, ac: Position, br: ID, bc: Position) -> bool {
 }
 
 
-fn eq_part_ass(nogood: &Board, curr_board: &Board) -> bool {
-    use Position::{Col, Unass};
-
-    let small_length = cmp::min(nogood.len(), curr_board.len());
-    for i in 0..small_length {
-        // if either pa1[i] or pa2[i] is Unass, it goes to the next value of i
-        // but that's not the behaviour I want. If the predecessor is
-        // I suppose that's ok. 
-        let _ = match nogood[i] {
-            Unass => (),
-            Col(col1) => {
-                match curr_board[i] {
-                    Unass => return false,
-                    Col(col2) => if col1 != col2 {return false},
-                }
-            },
-        };
-    }
-    return true;
-}
-
-fn make_agents(num_agents: usize) -> Vec<AgentState> {
+// every solution has a mirror image with the first row's queen in the left
+// half, so with break_symmetry row 0 only tries those columns
+fn make_agents(num_agents: usize, break_symmetry: bool) -> Vec<AgentState> {
     let mut agents: Vec<AgentState> = vec![];
-    let (mut txs, mut rxs) = make_channels(num_agents);
+    let (txs, mut rxs) = make_channels(num_agents);
     for i in 0..num_agents {
         if let Some(rx) = rxs.pop() {
             let agent = AgentState {
                 id: i,
                 pos: Board::Board(vec![Position::Col(0); num_agents]),
-                no_goods: vec![],
+                no_goods: NogoodStore::new(i),
                 txs: txs.clone(),
-                rx: rx,
-                mess2send: vec![Message::Empty(i); num_agents],
+                rx,
+                mess2send: vec![Message::Empty; num_agents],
+                no_solution: false,
+                domain: match break_symmetry && i == 0 {
+                    true => num_agents.div_ceil(2),
+                    false => num_agents,
+                },
+                priorities: vec![0; num_agents],
+                sent_no_goods: vec![],
+                pending_ok: false,
+                rng: 0x2545_f491_4f6c_dd1d ^ (i as u64 + 1),
+                order_stamps: vec![0; num_agents],
+                got_nogood: false,
             };
             agents.push(agent);
         };
@@ -123,8 +163,8 @@ fn make_agents(num_agents: usize) -> Vec<AgentState> {
 }
 
 
-fn try_to_inc_pos(state: &mut AgentState, num_agents : usize) -> bool {
-    let max_pos = num_agents - 1;
+fn try_to_inc_pos(state: &mut AgentState) -> bool {
+    let max_pos = state.domain - 1;
 
     // must check for it being too big here because when we found that a Nogood
     // prevented an otherwise acceptable state, we increment a position,
@@ -148,13 +188,13 @@ fn try_to_inc_pos(state: &mut AgentState, num_agents : usize) -> bool {
         for i in 0..state.id {
             found_flag = consistent(i, state.pos[i], state.id,
                                                 Position::Col(col));
-            if false == found_flag {break;}
+            if !found_flag {break;}
         }
-        if false == found_flag {continue;}
+        if !found_flag {continue;}
         state.pos[state.id] = Position::Col(col);
         break;
     }
-    if false == found_flag {
+    if !found_flag {
         state.pos[state.id] = Position::Col(0);
         return false;
     }
@@ -163,19 +203,58 @@ fn try_to_inc_pos(state: &mut AgentState, num_agents : usize) -> bool {
 }
 
 
+// the first assigned predecessor whose queen attacks col, or the other
+// queens of a stored nogood that rules col out, in the current view
+fn column_culprits(state: &AgentState, col: usize) -> Option<Vec<ID>> {
+    let id = state.id;
+    for i in 0..id {
+        if !consistent(i, state.pos[i], id, Position::Col(col)) {
+            return Some(vec![i]);
+        }
+    }
+    state.no_goods.find(col, &state.pos)
+        .map(|pairs| pairs.iter().map(|(i, _)| *i).collect())
+}
+
+// the nogood explaining why no column is left: the union of the culprits of
+// every column, so it only holds queens that actually rule columns out. A
+// column without an explanation falls back to all assigned predecessors
+fn explain(state: &AgentState) -> Board {
+    let mut culprit = vec![false; state.id];
+    for col in 0..state.domain {
+        match column_culprits(state, col) {
+            Some(ids) => for i in ids {culprit[i] = true;},
+            None => for (i, c) in culprit.iter_mut().enumerate() {
+                *c = *c || state.pos[i] != Position::Unass;
+            },
+        }
+    }
+    let len = culprit.iter().rposition(|c| *c).map_or(0, |last| last + 1);
+    Board::Board((0..len).map(|i| match culprit[i] {
+        true => state.pos[i],
+        false => Position::Unass,
+    }).collect())
+}
+
+
 fn update_pos(state: &mut AgentState, num_agents: usize) -> bool {
-    let mut backtrack_depth = 0;
-    while false == try_to_inc_pos(state, num_agents) {
-        backtrack_depth = backtrack_depth + 1;
-        let pred = state.id - backtrack_depth;
+    let mut backtracked = vec![false; num_agents];
+    while !try_to_inc_pos(state) {
+        let nogood = explain(state);
+        // an empty nogood means no assignment of the others helps, and then
+        // there is no solution at all
+        if nogood.len() == 0 {
+            state.no_solution = true;
+            return false;
+        }
+        // the nogood goes to its lowest priority queen
+        let pred = nogood.len() - 1;
+        backtracked[pred] = true;
 
         //send Nogood
-        let nogood = match &state.pos {
-            Board::Board(pos_vec) => pos_vec[0..(pred + 1)].to_vec(),
-        };
         // this needs to be a tx
         // used to be states[pred].no_goods.push(nogood);
-        state.mess2send[pred] = Message::Nogood(state.id, Board::Board(nogood));
+        state.mess2send[pred] = Message::Nogood(state.id, nogood);
         
         /* used to be
         state.txs[pred].send(Message::Nogood(state.id, Board::Board(nogood))).unwrap();
@@ -186,11 +265,11 @@ fn update_pos(state: &mut AgentState, num_agents: usize) -> bool {
         state.pos[pred] = Position::Unass;
 
     }
-    if backtrack_depth > 0 {
-        for i in 0..num_agents {
-            if state.id - backtrack_depth <= i && i < state.id {continue;}
-            // state.txs[i].send(Message::Empty(state.id)).unwrap();
-            state.mess2send[i] = Message::Empty(state.id);
+    if backtracked.contains(&true) {
+        for (i, sent_nogood) in backtracked.iter().enumerate() {
+            if *sent_nogood {continue;}
+            // state.txs[i].send(Message::Empty).unwrap();
+            state.mess2send[i] = Message::Empty;
         }
         return false;
     }
@@ -204,19 +283,17 @@ fn run_agent_rec(state: &mut AgentState, num_agents: usize) -> bool {
 
     // then look to see if the current agent has a consistent assignment.
     // if not, send a Nogood. 
-    if false == update_pos(state, num_agents) {return false;}
+    if !update_pos(state, num_agents) {return false;}
 
     // Now that a consistent assignment has been found, check to see if it's
     // ruled out by a Nogood.
-    for nogood in &state.no_goods {
-        if eq_part_ass(&nogood, &state.pos) {
-            let col: usize;
-            if let Position::Col(_col) = state.pos[state.id] {
-                col = _col;
-            } else {unreachable!();}
-            state.pos[state.id] = Position::Col(col + 1);
-            return run_agent_rec(state, num_agents);
-        }
+    let col: usize;
+    if let Position::Col(_col) = state.pos[state.id] {
+        col = _col;
+    } else {unreachable!();}
+    if state.no_goods.rules_out(col, &state.pos) {
+        state.pos[state.id] = Position::Col(col + 1);
+        return run_agent_rec(state, num_agents);
     }
     true
 }
@@ -230,7 +307,7 @@ fn run_agent_rec(state: &mut AgentState, num_agents: usize) -> bool {
 fn run_agent(state: &mut AgentState, num_agents: usize) -> bool {
     
     let old_state_col = state.pos[state.id];
-    if false == run_agent_rec(state, num_agents) {return false;}
+    if !run_agent_rec(state, num_agents) {return false;}
 
     // if the consistent assignment is not ruled out by a Nogood, then you
     // should send ok messages to the other agents
@@ -239,21 +316,20 @@ fn run_agent(state: &mut AgentState, num_agents: usize) -> bool {
         return false;
     }
 
-    return true;
+    true
 }
 
 fn send_oks(state: &mut AgentState, num_agents: usize) {
-    use Message::{Empty, Idle, Ok, Nogood};
     for pred in 0..(state.id + 1) {
-        //state.txs[pred].send(Message::Empty(state.id)).unwrap();
-        state.mess2send[pred] = Message::Empty(state.id);
+        //state.txs[pred].send(Message::Empty).unwrap();
+        state.mess2send[pred] = Message::Empty;
     }
     for succ in (state.id + 1)..num_agents {
         let pos = state.pos[state.id];
         // pos is automatically cloned here. but it's possible I'm trying
         // to move out of a vector. maybe it's cloned above as well
         //state.txs[succ].send(Message::Ok(state.id, pos)).unwrap();
-        state.mess2send[succ] = Message::Ok(state.id, pos);
+        state.mess2send[succ] = Message::Ok(state.id, pos, 0);
     }
 
 }
@@ -274,11 +350,11 @@ fn make_channels(num_agents : usize)
 }
 
 
-fn print_board(state : &AgentState, num_agents : usize) {
-    let i = num_agents;
-    println!("{:?}", state.pos);
-    for ii in 0..i {
-        if let Position::Col(col) = state.pos[ii] {
+fn print_board(board: &Board) {
+    let num_agents = board.len();
+    println!("{:?}", board);
+    for ii in 0..num_agents {
+        if let Position::Col(col) = board[ii] {
             for _ in 0..col {print!("-");}
             print!("1");
             for _ in (col + 1)..num_agents {print!("-");}
@@ -286,37 +362,107 @@ fn print_board(state : &AgentState, num_agents : usize) {
         println!();
     }
     println!();
-} 
+}
+
+// the column of every row, with -1 for a row that has no queen
+fn columns(board: &Board) -> Vec<isize> {
+    (0..board.len()).map(|i| match board[i] {
+        Position::Col(col) => col as isize,
+        Position::Unass => -1,
+    }).collect()
+}
+
+fn is_solution(board: &Board) -> bool {
+    for a in 0..board.len() {
+        if board[a] == Position::Unass {return false;}
+        for b in 0..a {
+            if !consistent(b, board[b], a, board[a]) {return false;}
+        }
+    }
+    true
+}
+
+// asynchronous agents have no rounds, only the most messages one of them
+// handled
+fn rounds_label(config: &Config) -> &'static str {
+    match config.runtime {
+        Runtime::Sync => "rounds",
+        Runtime::Async => "steps",
+    }
+}
+
+fn print_result(result: &RunResult, config: &Config, duration: Duration) {
+    let cols = columns(&result.board);
+    match config.format {
+        Format::Board => {
+            if result.board.len() > 100 {
+                println!("board too large to draw, use --format columns or json");
+                println!("solved: {}", result.solved);
+            } else if result.solved {
+                print_board(&result.board);
+            } else {
+                println!("no solution found");
+            }
+        },
+        Format::Columns => println!("{:?}", cols),
+        Format::Json => {
+            let cols: Vec<String> = cols.iter().map(|col| col.to_string()).collect();
+            let mut json = format!("{{\"n\": {}, \"solved\": {}, \"columns\": [{}]",
+                                   result.board.len(), result.solved, cols.join(", "));
+            if config.timing {
+                json.push_str(&format!(", \"{}\": {}, \"messages\": {}, \"detection_messages\": {}, \"time_ms\": {:.3}, \"nogoods\": {}, \"peak_nogoods\": {}",
+                                       rounds_label(config), result.rounds, result.messages, result.detection_messages, duration.as_secs_f64() * 1000.0,
+                                       result.nogoods, result.peak_nogoods));
+            }
+            json.push('}');
+            println!("{}", json);
+            return;
+        },
+    }
+    if config.timing {
+        println!("{}: {}", rounds_label(config), result.rounds);
+        println!("messages: {}, {} more to detect termination", result.messages, result.detection_messages);
+        println!("nogoods: {} resident, at most {} in one agent",
+                 result.nogoods, result.peak_nogoods);
+        println!("time: {:?}", duration);
+    }
+}
 
 // receive messages. Updates local view and puts nogoods in the vector
 // returns idle iff it receives idle from every other agent
 fn receive_messages(num_agents: usize, state: &mut AgentState) -> bool {
-    use Message::{Empty, Idle, Ok, Nogood};
     let mut idle = true;
-    for i in 0..num_agents {
-        let _ = match state.rx.recv().unwrap() {
-            Message::Idle(sender) => {
+    for _ in 0..num_agents {
+        match state.rx.recv().unwrap() {
+            Message::Idle => {
             },
-            Message::Empty(sender) => {
+            Message::Empty => {
                 idle = false;
             },
-            Message::Ok(sender, pos) => {
+            Message::Ok(sender, pos, _) => {
                 idle = false;
                 if state.pos[sender] != pos {
+                    if let Position::Col(col) = pos {
+                        state.no_goods.remove_obsolete(sender, col);
+                    }
                     state.pos[state.id] = Position::Col(0);
                     for succ in (state.id + 1)..num_agents {
                         state.mess2send[succ] = 
-                            Message::Ok(state.id, Position::Col(0));
+                            Message::Ok(state.id, Position::Col(0), 0);
                     }
                 }
                 state.pos[sender] = pos;
-                ()
             },
-            Message::Nogood(sender, nogood) => {
+            Message::Nogood(_, nogood) => {
                 idle = false;
-                state.no_goods.push(nogood);
-                ()
+                // only nogoods that agree with the view are kept, which
+                // bounds how many an agent holds; the Ok above drops the
+                // ones that stop agreeing
+                if state.no_goods.compatible(&nogood, &state.pos) {
+                    state.no_goods.insert(&nogood);
+                }
             },
+            Message::Order(..) | Message::Token(..) | Message::Stop => unreachable!(),
         };
     }
     idle
@@ -324,65 +470,274 @@ fn receive_messages(num_agents: usize, state: &mut AgentState) -> bool {
 
 
 
-fn send_messages(state: &mut AgentState) {
-    let mut mess = Message::Idle(state.id);
+// returns how many of the messages were more than Idle or Empty
+fn send_messages(state: &mut AgentState) -> usize {
+    let mut mess = Message::Idle;
+    let mut sent = 0;
     for i in 0..state.mess2send.len() {
         mem::swap(&mut state.mess2send[i], &mut mess);
+        if !matches!(mess, Message::Idle | Message::Empty) {
+            sent += 1;
+        }
         state.txs[i].send(mess).unwrap();
-        mess = Message::Idle(state.id);
+        mess = Message::Idle;
     }
+    sent
 }
 
 
-fn main() {
-    let num_agents = 12 as usize;
-    let mut states = make_agents(num_agents);
+// runs one thread per agent in synchronous rounds until every agent is idle,
+// an agent proves there is no solution, or max_rounds is reached
+fn solve(num_agents: usize, max_rounds: Option<usize>, algorithm: Algorithm,
+         break_symmetry: bool) -> RunResult {
+    let mut states = make_agents(num_agents, break_symmetry);
 
     let mut handles = vec![];
     let barrier = Arc::new(Barrier::new(num_agents));
     let barrier1 = Arc::new(Barrier::new(num_agents));
+    let no_solution = Arc::new(AtomicBool::new(false));
     for _ in 0..num_agents {
         let c = barrier.clone();
         let c1 = barrier1.clone();
-        let _ = match states.pop() {
-            None => (),
-            Some(mut state) => {
-                let handle = thread::spawn(move || {
-                    let mut idle = false;
-                    loop {
-                       c.wait();
-                        // run the agent, including asynchronously
-                        //sending messages to every other agent
-                        idle = run_agent(&mut state, num_agents) && idle;
-                        send_messages(&mut state);
+        let no_sol = no_solution.clone();
+        if let Some(mut state) = states.pop() {
+            let handle = thread::spawn(move || {
+                let mut rounds = 0;
+                let mut messages = 0;
+                let mut detection_messages = 0;
+                loop {
+                    // every agent counts the same rounds, so they all stop
+                    // together
+                    if max_rounds.is_some_and(|max| rounds >= max) {
+                        break;
+                    }
+                    rounds += 1;
+                    c.wait();
+                    // run the agent, including asynchronously
+                    //sending messages to every other agent
+                    match algorithm {
+                        Algorithm::Abt => {run_agent(&mut state, num_agents);},
+                        Algorithm::Awc => awc::run_agent(&mut state, num_agents),
+                        Algorithm::AbtDo => abt_do::run_agent(&mut state, num_agents),
+                        _ => unreachable!(),
+                    }
+                    if state.no_solution {
+                        no_sol.store(true, Ordering::SeqCst);
+                    }
+                    let sent = send_messages(&mut state);
+                    messages += sent;
+                    detection_messages += num_agents - sent;
+
+                    c1.wait();
+                    // synchronously wait for messages from every 
+                    //other agent
+                    let idle = match algorithm {
+                        Algorithm::Abt => receive_messages(num_agents, &mut state),
+                        Algorithm::Awc => awc::receive_messages(num_agents, &mut state),
+                        Algorithm::AbtDo => abt_do::receive_messages(num_agents, &mut state),
+                        _ => unreachable!(),
+                    };
+
+                    if idle || no_sol.load(Ordering::SeqCst) {
+                        break;
+                    }
 
-                        idle = true;
 
+                }
+                (state.id, state.pos[state.id], rounds,
+                 state.no_goods.len(), state.no_goods.peak(), messages, detection_messages)
+            });
+            handles.push(handle);
+        };
+    }
 
-                        c1.wait();
-                        // synchronously wait for messages from every 
-                        //other agent
-                        idle = receive_messages(num_agents, &mut state) && idle;
+    // here I think you have to join and determine when to cut the agents off
+    let mut board = Board::Board(vec![Position::Unass; num_agents]);
+    let mut rounds = 0;
+    let mut nogoods = 0;
+    let mut peak_nogoods = 0;
+    let mut messages = 0;
+    let mut detection_messages = 0;
+    for handle in handles {
+        let (id, pos, agent_rounds, agent_nogoods, agent_peak, agent_messages, agent_detection)
+            = handle.join().unwrap();
+        messages += agent_messages;
+        detection_messages += agent_detection;
+        board[id] = pos;
+        rounds = cmp::max(rounds, agent_rounds);
+        nogoods += agent_nogoods;
+        peak_nogoods = cmp::max(peak_nogoods, agent_peak);
+    }
+    let solved = !no_solution.load(Ordering::SeqCst) && is_solution(&board);
+    RunResult { board, rounds, solved, nogoods, peak_nogoods, messages, detection_messages }
+}
 
-                        if idle {
-                            break;
-                        }
 
+// --count and --all: every solution by sequential search, or only the
+// number of them from the bitboard solvers
+fn print_solutions(config: &Config) {
+    let now = Instant::now();
+    let n = config.num_agents;
+    let plain_count = !config.list_all && !config.unique;
+    let count = match config.algorithm {
+        Algorithm::Bitboard if plain_count => bitboard::count(n),
+        Algorithm::BitboardParallel if plain_count => bitboard::count_parallel(n, config.num_threads),
+        _ => {
+            let mut count = 0;
+            for cols in enumerate::Solutions::new(n, config.unique) {
+                count += 1;
+                if !config.list_all {continue;}
+                match config.format {
+                    Format::Board => print_board(&Board::Board(cols.into_iter().map(Position::Col).collect())),
+                    Format::Columns => println!("{:?}", cols),
+                    Format::Json => {
+                        let cols: Vec<String> = cols.iter().map(|col| col.to_string()).collect();
+                        println!("{{\"n\": {}, \"columns\": [{}]}}", n, cols.join(", "));
+                    },
+                }
+            }
+            count
+        },
+    };
+    let duration = Instant::now().duration_since(now);
+    let what = if config.unique {"unique solutions"} else {"solutions"};
+    match config.format {
+        Format::Json => {
+            let mut json = format!("{{\"n\": {}, \"unique\": {}, \"count\": {}", n, config.unique, count);
+            if config.timing {
+                json.push_str(&format!(", \"time_ms\": {:.3}", duration.as_secs_f64() * 1000.0));
+            }
+            json.push('}');
+            println!("{}", json);
+        },
+        _ => {
+            println!("{}: {}", what, count);
+            if config.timing {
+                println!("time: {:?}", duration);
+            }
+        },
+    }
+}
 
-                    }
-                    if state.id == num_agents - 1 {
-                        print_board(&state, num_agents)
-                    }
-                });
-                handles.push(handle);
-                ()
-            },
-        };
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let config = match cli::parse_args(&args[1..]) {
+        Ok(config) => config,
+        Err(message) => {
+            eprintln!("{}", message);
+            eprintln!("{}", cli::USAGE);
+            process::exit(2);
+        },
+    };
+
+    if config.count || config.list_all {
+        print_solutions(&config);
+        return;
     }
 
-    // here I think you have to join and determine when to cut the agents off
-    for handle in handles {
-        handle.join().unwrap();
+    let now = Instant::now();
+    let mut result = match config.algorithm {
+        Algorithm::MinConflicts => minconf::solve(config.num_agents, config.max_rounds, config.seed),
+        Algorithm::Bitboard => bitboard::solve(config.num_agents),
+        Algorithm::BitboardParallel => bitboard::solve_parallel(config.num_agents, config.num_threads),
+        _ if config.runtime == Runtime::Async => async_abt::solve(config.num_agents, config.max_rounds, config.break_symmetry),
+        _ => solve(config.num_agents, config.max_rounds, config.algorithm, config.break_symmetry),
+    };
+    let duration = Instant::now().duration_since(now);
+    if config.canonical && result.solved {
+        result.board = symmetry::canonical(&result.board);
+    }
+    print_result(&result, &config, duration);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Position::{Col, Unass};
+
+    fn agent(num_agents: usize, id: usize, view: &[Position]) -> AgentState {
+        let mut state = make_agents(num_agents, false).swap_remove(id);
+        for (i, pos) in view.iter().enumerate() {
+            state.pos[i] = *pos;
+        }
+        state
+    }
+
+    #[test]
+    fn nogoods_only_hold_culprits() {
+        // every column of row 5 is attacked, but row 3 only attacks columns
+        // that rows 0 to 2 already rule out
+        let mut state = agent(6, 5, &[Col(1), Col(4), Col(2), Col(0), Col(3), Col(0)]);
+        assert!(!update_pos(&mut state, 6));
+        match &state.mess2send[4] {
+            Message::Nogood(5, nogood) =>
+                assert_eq!(*nogood, Board::Board(vec![Col(1), Col(4), Col(2), Unass, Col(3)])),
+            message => panic!("expected a nogood, got {:?}", message),
+        }
+        assert!(matches!(state.mess2send[3], Message::Empty));
+        assert!(!state.no_solution);
+    }
+
+    #[test]
+    fn nogood_skips_over_innocent_rows() {
+        let mut state = agent(6, 5, &[Col(0), Col(2), Col(4), Col(1), Col(3), Col(0)]);
+        assert!(!update_pos(&mut state, 6));
+        match &state.mess2send[3] {
+            Message::Nogood(5, nogood) =>
+                assert_eq!(*nogood, Board::Board(vec![Col(0), Col(2), Col(4), Col(1)])),
+            message => panic!("expected a nogood, got {:?}", message),
+        }
+        // without row 3 everything is still attacked, now blaming row 4
+        assert!(matches!(state.mess2send[4], Message::Nogood(5, _)));
+        assert_eq!(state.pos[5], Col(3));
+    }
+
+    #[test]
+    fn empty_nogood_means_no_solution() {
+        let mut state = agent(2, 0, &[]);
+        state.no_goods.insert(&Board::Board(vec![Col(0)]));
+        state.no_goods.insert(&Board::Board(vec![Col(1)]));
+        assert!(!run_agent(&mut state, 2));
+        assert!(state.no_solution);
+    }
+
+    #[test]
+    fn incompatible_nogoods_are_forgotten() {
+        let mut state = agent(3, 1, &[Col(0), Col(2)]);
+        state.no_goods.insert(&Board::Board(vec![Col(0), Col(2)]));
+        let tx = state.txs[1].clone();
+        tx.send(Message::Ok(0, Col(2), 0)).unwrap();
+        tx.send(Message::Nogood(2, Board::Board(vec![Col(0), Col(1)]))).unwrap();
+        tx.send(Message::Nogood(2, Board::Board(vec![Col(2), Col(0)]))).unwrap();
+        assert!(!receive_messages(3, &mut state));
+        // the first nogood went with row 0's move, the second never matched
+        assert_eq!(state.no_goods.len(), 1);
+        assert!(state.no_goods.rules_out(0, &state.pos));
+        assert_eq!(state.no_goods.peak(), 1);
+    }
+
+    #[test]
+    fn abt_solves_boards() {
+        for n in [1, 4, 5, 8, 10] {
+            for break_symmetry in [false, true] {
+                let result = solve(n, Some(10_000), Algorithm::Abt, break_symmetry);
+                assert!(result.solved, "no solution for n = {}", n);
+                if break_symmetry {
+                    assert!(matches!(result.board[0], Col(col) if col <= (n - 1) / 2));
+                }
+            }
+        }
+        for n in [2, 3] {
+            assert!(!solve(n, Some(10_000), Algorithm::Abt, false).solved);
+            assert!(!solve(n, Some(10_000), Algorithm::Abt, true).solved);
+        }
     }
 
+    #[test]
+    fn async_runs_report_steps() {
+        let args = |line: &str| line.split_whitespace().map(String::from).collect::<Vec<_>>();
+        assert_eq!(rounds_label(&cli::parse_args(&args("--alg abt")).unwrap()), "steps");
+        assert_eq!(rounds_label(&cli::parse_args(&args("--alg abt --runtime sync")).unwrap()), "rounds");
+        assert_eq!(rounds_label(&cli::parse_args(&args("--alg awc")).unwrap()), "rounds");
+    }
 }
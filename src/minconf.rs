@@ -0,0 +1,192 @@
+// Min-conflicts local search for very large boards. Every row keeps one
+// queen; counters for columns and both diagonals give the number of queens
+// attacking any square in O(1), so a repair step costs O(n) and a million
+// queens fit in memory and time. The result is an ordinary Board, so the
+// same consistent/is_solution code can check it.
+use crate::{Board, Position, RunResult};
+
+// xorshift64*, seeded so runs can be repeated
+struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    fn new(seed: u64) -> Rng {
+        Rng { state: seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1 }
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        (self.state.wrapping_mul(0x2545_f491_4f6c_dd1d) >> 16) as usize % bound
+    }
+}
+
+struct Counters {
+    n: usize,
+    cols: Vec<u32>,
+    diag_sum: Vec<u32>,  // row + col
+    diag_diff: Vec<u32>, // row - col + n - 1
+}
+
+impl Counters {
+    fn new(n: usize) -> Counters {
+        Counters {
+            n,
+            cols: vec![0; n],
+            diag_sum: vec![0; 2 * n - 1],
+            diag_diff: vec![0; 2 * n - 1],
+        }
+    }
+
+    fn add(&mut self, row: usize, col: usize) {
+        self.cols[col] += 1;
+        self.diag_sum[row + col] += 1;
+        self.diag_diff[row + self.n - 1 - col] += 1;
+    }
+
+    fn remove(&mut self, row: usize, col: usize) {
+        self.cols[col] -= 1;
+        self.diag_sum[row + col] -= 1;
+        self.diag_diff[row + self.n - 1 - col] -= 1;
+    }
+
+    // queens attacking (row, col), not counting one standing there
+    fn attacks(&self, row: usize, col: usize) -> u32 {
+        self.cols[col] + self.diag_sum[row + col] + self.diag_diff[row + self.n - 1 - col]
+    }
+}
+
+// start from a random permutation, so columns never clash, built greedily:
+// each row tries a few of the unused columns for one with free diagonals
+fn initial_columns(n: usize, counters: &mut Counters, rng: &mut Rng) -> Vec<usize> {
+    const TRIES: usize = 32;
+    let mut cols: Vec<usize> = (0..n).collect();
+    for row in 0..n {
+        let mut pick = row;
+        for _ in 0..TRIES {
+            let j = row + rng.below(n - row);
+            pick = j;
+            if counters.attacks(row, cols[j]) == 0 {break;}
+        }
+        cols.swap(row, pick);
+        counters.add(row, cols[row]);
+    }
+    cols
+}
+
+// move the queen in row to the column where it is attacked least, breaking
+// ties at random
+fn repair(row: usize, cols: &mut [usize], counters: &mut Counters, rng: &mut Rng) {
+    let n = cols.len();
+    counters.remove(row, cols[row]);
+    let mut best = u32::MAX;
+    let mut best_col = cols[row];
+    let mut ties = 0;
+    for col in 0..n {
+        let attacks = counters.attacks(row, col);
+        if attacks < best {
+            best = attacks;
+            best_col = col;
+            ties = 1;
+        } else if attacks == best {
+            ties += 1;
+            if rng.below(ties) == 0 {best_col = col;}
+        }
+    }
+    cols[row] = best_col;
+    counters.add(row, best_col);
+}
+
+fn to_board(cols: &[usize]) -> Board {
+    Board::Board(cols.iter().map(|col| Position::Col(*col)).collect())
+}
+
+// linear-time version of is_solution, for boards too big for the pairwise
+// check
+pub fn attack_free(board: &Board) -> bool {
+    let n = board.len();
+    if n == 0 {return true;}
+    let mut counters = Counters::new(n);
+    for row in 0..n {
+        match board[row] {
+            Position::Unass => return false,
+            Position::Col(col) if col >= n => return false,
+            Position::Col(col) => {
+                if counters.attacks(row, col) > 0 {return false;}
+                counters.add(row, col);
+            },
+        }
+    }
+    true
+}
+
+// min-conflicts search on an n by n board. max_steps bounds the number of
+// queen moves (default 100 n); rounds in the result counts the moves made
+pub fn solve(n: usize, max_steps: Option<usize>, seed: u64) -> RunResult {
+    let max_steps = max_steps.unwrap_or(100 * n);
+    let mut rng = Rng::new(seed);
+    let mut counters = Counters::new(n);
+    let mut cols = initial_columns(n, &mut counters, &mut rng);
+
+    // rows that were in conflict at the last scan; stale entries are skipped
+    let mut conflicted: Vec<usize> = vec![];
+    let mut steps = 0;
+    while steps < max_steps {
+        if conflicted.is_empty() {
+            conflicted = (0..n).filter(|&row| counters.attacks(row, cols[row]) > 3).collect();
+            if conflicted.is_empty() {break;}
+        }
+        let row = conflicted.swap_remove(rng.below(conflicted.len()));
+        // the queen itself shows up once on each of its three lines
+        if counters.attacks(row, cols[row]) == 3 {continue;}
+        repair(row, &mut cols, &mut counters, &mut rng);
+        steps += 1;
+    }
+    let board = to_board(&cols);
+    let solved = attack_free(&board);
+    RunResult { board, rounds: steps, solved, nogoods: 0, peak_nogoods: 0, messages: 0, detection_messages: 0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::is_solution;
+
+    #[test]
+    fn small_boards() {
+        for n in [1, 4, 5, 8, 12, 50, 200] {
+            let result = solve(n, None, n as u64);
+            assert!(result.solved, "no solution for n = {}", n);
+            assert!(is_solution(&result.board));
+            assert!(attack_free(&result.board));
+        }
+    }
+
+    #[test]
+    fn impossible_boards_give_up() {
+        for n in [2, 3] {
+            let result = solve(n, Some(1000), 1);
+            assert!(!result.solved);
+            assert_eq!(result.rounds, 1000);
+            assert!(!attack_free(&result.board));
+        }
+    }
+
+    #[test]
+    fn large_board() {
+        let result = solve(100_000, None, 7);
+        assert!(result.solved);
+        assert!(attack_free(&result.board));
+    }
+
+    #[test]
+    fn attack_free_matches_is_solution() {
+        let boards = [vec![1, 3, 0, 2], vec![2, 0, 3, 1], vec![0, 1, 3, 2], vec![2, 0, 3, 3]];
+        for cols in boards.iter() {
+            let board = to_board(cols);
+            assert_eq!(attack_free(&board), is_solution(&board));
+        }
+    }
+}
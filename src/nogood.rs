@@ -0,0 +1,281 @@
+// The nogoods an agent has received, indexed first by the agent's own column
+// and then by a trie over the other (agent, column) pairs in increasing agent
+// order. A lookup only walks the branches that agree with the current view
+// instead of comparing the view with every nogood ever received.
+use std::cmp;
+use std::collections::{BTreeMap, HashMap};
+use crate::{Board, Position, ID};
+
+#[derive(Default)]
+struct Trie {
+    end: bool,
+    children: BTreeMap<ID, HashMap<usize, Trie>>,
+}
+
+impl Trie {
+    fn is_empty(&self) -> bool {
+        !self.end && self.children.is_empty()
+    }
+
+    fn insert(&mut self, pairs: &[(ID, usize)]) {
+        match pairs.split_first() {
+            None => self.end = true,
+            Some((&(agent, col), rest)) => self.children.entry(agent).or_default()
+                .entry(col).or_default().insert(rest),
+        }
+    }
+
+    // a stored nogood whose agents all pass keep and sit on their column in
+    // view, as the pairs on its path
+    fn find<F>(&self, view: &Board, keep: &F, path: &mut Vec<(ID, usize)>) -> bool
+    where F: Fn(ID) -> bool {
+        if self.end {return true;}
+        for (&agent, by_col) in &self.children {
+            if agent >= view.len() || !keep(agent) {continue;}
+            if let Position::Col(col) = view[agent] {
+                if let Some(child) = by_col.get(&col) {
+                    path.push((agent, col));
+                    if child.find(view, keep, path) {return true;}
+                    path.pop();
+                }
+            }
+        }
+        false
+    }
+
+    fn count(&self) -> usize {
+        let below: usize = self.children.values()
+            .flat_map(|by_col| by_col.values()).map(Trie::count).sum();
+        below + self.end as usize
+    }
+
+    // remove every nogood containing all of pairs; returns how many went
+    fn remove_supersets(&mut self, pairs: &[(ID, usize)]) -> usize {
+        let (&(agent, col), rest) = match pairs.split_first() {
+            None => {
+                let removed = self.count();
+                *self = Trie::default();
+                return removed;
+            },
+            Some(split) => split,
+        };
+        let mut removed = 0;
+        for (&child_agent, by_col) in self.children.range_mut(..=agent) {
+            if child_agent == agent {
+                if let Some(child) = by_col.get_mut(&col) {
+                    removed += child.remove_supersets(rest);
+                }
+            } else {
+                for child in by_col.values_mut() {
+                    removed += child.remove_supersets(pairs);
+                }
+            }
+        }
+        self.prune();
+        removed
+    }
+
+    // remove every nogood that puts agent anywhere but col
+    fn remove_obsolete(&mut self, agent: ID, col: usize) -> usize {
+        let mut removed = 0;
+        for (&child_agent, by_col) in self.children.range_mut(..=agent) {
+            if child_agent == agent {
+                by_col.retain(|&child_col, child| {
+                    if child_col == col {return true;}
+                    removed += child.count();
+                    false
+                });
+            } else {
+                for child in by_col.values_mut() {
+                    removed += child.remove_obsolete(agent, col);
+                }
+            }
+        }
+        self.prune();
+        removed
+    }
+
+    fn prune(&mut self) {
+        for by_col in self.children.values_mut() {
+            by_col.retain(|_, child| !child.is_empty());
+        }
+        self.children.retain(|_, by_col| !by_col.is_empty());
+    }
+}
+
+pub struct NogoodStore {
+    owner: ID,
+    by_value: HashMap<usize, Trie>,
+    len: usize,
+    peak: usize,
+}
+
+impl NogoodStore {
+    pub fn new(owner: ID) -> NogoodStore {
+        NogoodStore { owner, by_value: HashMap::new(), len: 0, peak: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    // the most nogoods ever resident at once
+    pub fn peak(&self) -> usize {
+        self.peak
+    }
+
+    // whether nogood agrees with every assigned entry of view, ignoring the
+    // owner's own entry. One that does not can never match again until the
+    // agents in it move back, and ABT does not need to keep it
+    pub fn compatible(&self, nogood: &Board, view: &Board) -> bool {
+        (0..cmp::min(nogood.len(), view.len())).all(|i| {
+            i == self.owner || nogood[i] == Position::Unass
+                || view[i] == Position::Unass || nogood[i] == view[i]
+        })
+    }
+
+    // the owner's column and the other assigned entries of a nogood
+    fn split(&self, nogood: &Board) -> Option<(usize, Vec<(ID, usize)>)> {
+        let own = match self.owner < nogood.len() {
+            true => nogood[self.owner],
+            false => Position::Unass,
+        };
+        let own = match own {
+            Position::Col(col) => col,
+            Position::Unass => return None,
+        };
+        let pairs = (0..nogood.len()).filter(|&i| i != self.owner)
+            .filter_map(|i| match nogood[i] {
+                Position::Col(col) => Some((i, col)),
+                Position::Unass => None,
+            }).collect();
+        Some((own, pairs))
+    }
+
+    // stores the nogood unless a stored one already implies it, dropping the
+    // stored ones it implies. Nogoods that do not mention the owner are
+    // ignored. Returns whether it was stored
+    pub fn insert(&mut self, nogood: &Board) -> bool {
+        let (own, pairs) = match self.split(nogood) {
+            Some(split) => split,
+            None => return false,
+        };
+        let trie = self.by_value.entry(own).or_default();
+        if trie.find(nogood, &|_| true, &mut vec![]) {
+            return false;
+        }
+        self.len -= trie.remove_supersets(&pairs);
+        trie.insert(&pairs);
+        self.len += 1;
+        self.peak = cmp::max(self.peak, self.len);
+        true
+    }
+
+    // the other (agent, column) pairs of a nogood that rules out col for the
+    // owner given view, only looking at nogoods whose agents all pass keep
+    pub fn find_where<F>(&self, col: usize, view: &Board, keep: F) -> Option<Vec<(ID, usize)>>
+    where F: Fn(ID) -> bool {
+        let trie = self.by_value.get(&col)?;
+        let mut path = vec![];
+        match trie.find(view, &keep, &mut path) {
+            true => Some(path),
+            false => None,
+        }
+    }
+
+    pub fn find(&self, col: usize, view: &Board) -> Option<Vec<(ID, usize)>> {
+        self.find_where(col, view, |_| true)
+    }
+
+    pub fn rules_out(&self, col: usize, view: &Board) -> bool {
+        self.find(col, view).is_some()
+    }
+
+    // agent moved to col: nogoods on its old column can no longer match
+    pub fn remove_obsolete(&mut self, agent: ID, col: usize) {
+        if agent == self.owner {return;}
+        for trie in self.by_value.values_mut() {
+            self.len -= trie.remove_obsolete(agent, col);
+        }
+        self.by_value.retain(|_, trie| !trie.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Position::{Col, Unass};
+
+    fn board(cols: &[Option<usize>]) -> Board {
+        Board::Board(cols.iter().map(|col| match col {
+            Some(col) => Col(*col),
+            None => Unass,
+        }).collect())
+    }
+
+    #[test]
+    fn lookup_follows_the_view() {
+        let mut store = NogoodStore::new(3);
+        assert!(store.insert(&board(&[Some(0), None, Some(4), Some(1)])));
+        assert!(store.insert(&board(&[Some(2), Some(0), None, Some(1)])));
+        assert_eq!(store.len(), 2);
+
+        let view = board(&[Some(0), Some(5), Some(4), Some(1)]);
+        assert_eq!(store.find(1, &view), Some(vec![(0, 0), (2, 4)]));
+        assert!(!store.rules_out(2, &view));
+        let view = board(&[Some(2), Some(0), Some(3), Some(0)]);
+        assert_eq!(store.find(1, &view), Some(vec![(0, 2), (1, 0)]));
+        let view = board(&[Some(0), Some(0), None, Some(0)]);
+        assert!(!store.rules_out(1, &view));
+    }
+
+    #[test]
+    fn subsumption() {
+        let mut store = NogoodStore::new(3);
+        assert!(store.insert(&board(&[Some(0), Some(2), Some(4), Some(1)])));
+        assert!(store.insert(&board(&[Some(1), Some(2), Some(4), Some(1)])));
+        assert!(!store.insert(&board(&[Some(0), Some(2), Some(4), Some(1)])));
+        // this one implies both stored ones
+        assert!(store.insert(&board(&[None, Some(2), Some(4), Some(1)])));
+        assert_eq!(store.len(), 1);
+        assert!(!store.insert(&board(&[Some(3), Some(2), Some(4), Some(1)])));
+        assert!(store.insert(&board(&[Some(3), Some(2), Some(4), Some(0)])));
+        assert_eq!(store.len(), 2);
+        assert!(store.insert(&board(&[None, None, None, Some(1)])));
+        assert_eq!(store.len(), 2);
+    }
+
+    #[test]
+    fn obsolete_nogoods_go() {
+        let mut store = NogoodStore::new(2);
+        store.insert(&board(&[Some(0), Some(3), Some(1)]));
+        store.insert(&board(&[Some(1), Some(3), Some(1)]));
+        store.insert(&board(&[None, Some(4), Some(2)]));
+        store.insert(&board(&[Some(0), None, Some(0)]));
+        store.remove_obsolete(1, 3);
+        assert_eq!(store.len(), 3);
+        store.remove_obsolete(0, 1);
+        assert_eq!(store.len(), 1);
+        assert!(store.rules_out(1, &board(&[Some(1), Some(3), None])));
+        assert!(!store.rules_out(0, &board(&[Some(0), Some(3), None])));
+        // the owner's own moves never make its nogoods obsolete
+        store.remove_obsolete(2, 5);
+        assert_eq!(store.len(), 1);
+        assert_eq!(store.peak(), 4);
+
+        let view = board(&[Some(1), Some(2), None]);
+        assert!(store.compatible(&board(&[Some(1), None, Some(4)]), &view));
+        assert!(!store.compatible(&board(&[Some(1), Some(3), Some(4)]), &view));
+        assert!(store.compatible(&board(&[Some(1), Some(2), Some(4), Some(0)]), &view));
+    }
+
+    #[test]
+    fn filtered_lookup_and_later_agents() {
+        let mut store = NogoodStore::new(1);
+        store.insert(&board(&[None, Some(2), Some(0), Some(3)]));
+        let view = board(&[Some(1), Some(0), Some(0), Some(3)]);
+        assert!(store.rules_out(2, &view));
+        assert!(store.find_where(2, &view, |agent| agent != 3).is_none());
+        assert!(!store.insert(&board(&[Some(0), None, Some(0)])));
+    }
+}
@@ -7,10 +7,8 @@ type Nogood = Board;
 
 
 struct AgentState {
-    id: usize,
     pos: Board,
     no_goods: Vec<Nogood>,
-    new_no_goods: Vec<Nogood>,
     oks: Vec<(usize,isize)>,
 }
     
@@ -29,7 +27,7 @@ fn consistent(ar: usize, ac: Pos, br: usize, bc: Pos) -> bool {
     if (ar as isize) - (br as isize) == bc - ac {
         return false;
     }
-    return true;
+    true
 }
 
 
@@ -45,17 +43,15 @@ fn eq_part_ass(pa1: &Board, pa2: &Board) -> bool {
             return false;
         }
     }
-    return true;
+    true
 }
 
 fn make_agents(num_agents: usize) -> Vec<AgentState> {
     let mut agents: Vec<AgentState> = vec![];
-    for i in 0..num_agents {
+    for _ in 0..num_agents {
         let agent = AgentState {
-            id: i,
             pos: vec![0; num_agents],
             no_goods: vec![],
-            new_no_goods: vec![],
             oks: vec![],
         };
         agents.push(agent);
@@ -64,7 +60,7 @@ fn make_agents(num_agents: usize) -> Vec<AgentState> {
 }
 
 
-fn update_pos(agent: usize, states: &mut Vec<AgentState>,
+fn update_pos(agent: usize, states: &mut [AgentState],
               num_agents : isize) -> bool {
     let max_pos = num_agents - 1;
 
@@ -83,16 +79,16 @@ fn update_pos(agent: usize, states: &mut Vec<AgentState>,
     for pos in start..(max_pos + 1) {
         found_flag = true;
         for i in 0..agent {
-            if false == consistent(i, states[agent].pos[i], agent, pos) {
+            if !consistent(i, states[agent].pos[i], agent, pos) {
                 found_flag = false;
                 break;
             }
         }
-        if false == found_flag {continue;}
+        if !found_flag {continue;}
         states[agent].pos[agent] = pos;
         break;
     }
-    if false == found_flag {
+    if !found_flag {
         states[agent].pos[agent] = 0;
         return false;
     }
@@ -101,13 +97,35 @@ fn update_pos(agent: usize, states: &mut Vec<AgentState>,
 }
 
 
+// the nogood for an agent with no column left: for every column, the first
+// predecessor attacking it, all other entries -1. Columns that were only
+// ruled out by a nogood blame every assigned predecessor
+fn explain(agent: usize, states: &[AgentState], num_agents: isize) -> Nogood {
+    let pos = &states[agent].pos;
+    let mut culprit = vec![false; agent];
+    for col in 0..num_agents {
+        match (0..agent).find(|i| !consistent(*i, pos[*i], agent, col)) {
+            Some(i) => culprit[i] = true,
+            None => for i in 0..agent {
+                if pos[i] != -1 {culprit[i] = true;}
+            },
+        }
+    }
+    let mut nogood: Nogood = (0..agent).map(|i| if culprit[i] {pos[i]} else {-1}).collect();
+    while nogood.last() == Some(&-1) {
+        nogood.pop();
+    }
+    nogood
+}
+
+
 // returns true found a consistent assignment
-fn run_agent(agent: usize, states: &mut Vec<AgentState>,
+fn run_agent(agent: usize, states: &mut [AgentState],
              num_agents: isize) -> bool {
 
     // first update the local view from the ok messages queue.
     // in this sequential version, they're already updated.
-    while states[agent].oks.len() > 0 {
+    while !states[agent].oks.is_empty() {
         match states[agent].oks.pop() {
             None => break,
             Some(update) => {
@@ -124,12 +142,16 @@ fn run_agent(agent: usize, states: &mut Vec<AgentState>,
     // if not, send a Nogood. If so, check to make sure that it's not ruled
     // out by a Nogood.
     let mut backtrack_depth = 0;
-    while false == update_pos(agent, states, num_agents) {
-        backtrack_depth = backtrack_depth + 1;
-        let pred = ((agent as isize) - backtrack_depth) as usize;
+    while !update_pos(agent, states, num_agents) {
+        backtrack_depth += 1;
+        let nogood = explain(agent, states, num_agents);
+        // with nobody to blame there is no solution
+        if nogood.is_empty() {
+            return false;
+        }
+        let pred = nogood.len() - 1;
 
         //send Nogood
-        let nogood = states[agent].pos[0..(pred + 1)].to_vec();
         states[pred].no_goods.push(nogood);
 
         states[agent].pos[agent] = 0;
@@ -142,12 +164,12 @@ fn run_agent(agent: usize, states: &mut Vec<AgentState>,
 
     // Now that a consistent assignment has been found, check to see if it's
     // ruled out by a Nogood.
-    while states[agent].no_goods.len() > 0 {
+    while !states[agent].no_goods.is_empty() {
         match states[agent].no_goods.pop() {
             None => break,
             Some(no_good) =>
                 if eq_part_ass(&no_good, &states[agent].pos) {
-                    states[agent].pos[agent] = states[agent].pos[agent] + 1;
+                    states[agent].pos[agent] += 1;
                     return run_agent(agent, states, num_agents);
                 },
         }
@@ -160,7 +182,7 @@ fn run_agent(agent: usize, states: &mut Vec<AgentState>,
         let new_pos = states[agent].pos[agent];
         states[succ].oks.push((agent, new_pos));
     }
-    return true;
+    true
 }
 
 
@@ -176,7 +198,7 @@ fn main() {
                 found_cons = run_agent(j, &mut states, num_agents)
                                 && found_cons;
             }
-            if found_cons == true {
+            if found_cons {
                 println!("{:?}", states[i - 1].pos);
                 for ii in 0..i {
                     for _ in 0..states[i-1].pos[ii] {print!("-");}
@@ -192,3 +214,24 @@ fn main() {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nogoods_are_smaller_than_the_prefix() {
+        // every column of row 5 is attacked, but row 3 only attacks columns
+        // that rows 0 to 2 already rule out
+        let mut states = make_agents(6);
+        states[5].pos = vec![1, 4, 2, 0, 3, 0];
+        let nogood = explain(5, &states, 6);
+        assert_eq!(nogood, vec![1, 4, 2, -1, 3]);
+
+        assert!(!run_agent(5, &mut states, 6));
+        let sent = &states[4].no_goods[0];
+        let prefix = &states[5].pos[0..5];
+        assert_eq!(sent.len(), prefix.len());
+        assert!(sent.iter().filter(|pos| **pos != -1).count() < prefix.len());
+    }
+}
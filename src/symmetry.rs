@@ -0,0 +1,76 @@
+// The eight symmetries of the square board: rotations by quarter turns and
+// the four reflections. A full placement is a permutation, row r holding its
+// queen in column cols[r], and every symmetry maps it to another placement.
+// The smallest of the eight images, comparing columns row by row, stands for
+// the whole orbit.
+use std::mem;
+use crate::{Board, Position};
+
+// the image of cols under symmetry t in 0..8: bit 0 mirrors the columns,
+// bit 1 mirrors the rows and bit 2 swaps rows and columns, which together
+// give all eight
+pub fn transform(cols: &[usize], t: usize) -> Vec<usize> {
+    let n = cols.len();
+    let mut image = vec![0; n];
+    for (row, &col) in cols.iter().enumerate() {
+        let (mut r, mut c) = (row, col);
+        if t & 1 != 0 {c = n - 1 - c;}
+        if t & 2 != 0 {r = n - 1 - r;}
+        if t & 4 != 0 {mem::swap(&mut r, &mut c);}
+        image[r] = c;
+    }
+    image
+}
+
+pub fn canonical_columns(cols: &[usize]) -> Vec<usize> {
+    (0..8).map(|t| transform(cols, t)).min().unwrap()
+}
+
+// the representative of board's orbit; board must have a queen in every row
+pub fn canonical(board: &Board) -> Board {
+    let cols: Vec<usize> = (0..board.len()).map(|i| match board[i] {
+        Position::Col(col) => col,
+        Position::Unass => panic!("canonical needs a full placement"),
+    }).collect();
+    Board::Board(canonical_columns(&cols).into_iter().map(Position::Col).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enumerate::Solutions;
+    use crate::minconf::attack_free;
+
+    #[test]
+    fn symmetries_map_solutions_to_solutions() {
+        let cols = vec![0, 4, 7, 5, 2, 6, 1, 3];
+        let mut images: Vec<Vec<usize>> = (0..8).map(|t| transform(&cols, t)).collect();
+        for image in &images {
+            let board = Board::Board(image.iter().map(|&col| Position::Col(col)).collect());
+            assert!(attack_free(&board));
+            assert_eq!(canonical_columns(image), canonical_columns(&cols));
+        }
+        assert_eq!(transform(&cols, 0), cols);
+        images.sort();
+        images.dedup();
+        assert_eq!(images.len(), 8);
+    }
+
+    #[test]
+    fn orbits_of_eight_queens() {
+        let solutions: Vec<Vec<usize>> = Solutions::new(8, false).collect();
+        assert_eq!(solutions.len(), 92);
+        let mut orbits: Vec<Vec<usize>> = solutions.iter().map(|cols| canonical_columns(cols)).collect();
+        orbits.sort();
+        orbits.dedup();
+        assert_eq!(orbits.len(), 12);
+        // the representative is the smallest image, so it starts in the left half
+        assert!(orbits.iter().all(|cols| cols[0] < 4));
+
+        let board = Board::Board(solutions[50].iter().map(|&col| Position::Col(col)).collect());
+        let Board::Board(canonical) = canonical(&board);
+        assert!(orbits.iter().any(|cols| {
+            cols.iter().zip(&canonical).all(|(&col, &pos)| pos == Position::Col(col))
+        }));
+    }
+}